@@ -0,0 +1,267 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub const USAGE: &str = "usage: rust-compiler-2 <command> [options] <input>...
+
+commands:
+    build                   parse, type check and write every target
+    check                   parse and type check only, nothing is written
+    emit --target <js|cpp>  parse, type check and write a single target
+
+options:
+    --out-dir <dir>         where generated files are written (default: output)
+    --target <js|cpp>       the target for the emit command
+    --message-format <fmt>  how errors are printed: human (default) or json (one object per line on stdout)
+    -h, --help              print this message
+
+inputs can be files or directories, directories are searched (recursively) for .bob files";
+
+pub const SOURCE_FILE_EXTENSION: &str = "bob";
+
+//exit codes, one per failure class so build scripts can tell them apart
+pub const EXIT_SUCCESS: u8 = 0;
+pub const EXIT_COMPILE_ERROR: u8 = 1;
+pub const EXIT_USAGE_ERROR: u8 = 2;
+pub const EXIT_IO_ERROR: u8 = 3;
+pub const EXIT_INTERNAL_ERROR: u8 = 101;
+
+pub fn exit_code(code: u8) -> ExitCode {
+    ExitCode::from(code)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitTarget {
+    Javascript,
+    Cpp,
+}
+
+impl EmitTarget {
+    pub const ALL: [EmitTarget; 2] = [EmitTarget::Javascript, EmitTarget::Cpp];
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "js" | "javascript" => Some(EmitTarget::Javascript),
+            "cpp" | "c++" => Some(EmitTarget::Cpp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Build,
+    Check,
+    Emit(EmitTarget),
+}
+
+#[derive(Debug)]
+pub struct CliOptions {
+    pub command: Command,
+    pub inputs: Vec<PathBuf>,
+    pub out_dir: PathBuf,
+    pub message_format: MessageFormat,
+}
+
+impl CliOptions {
+    pub fn targets(&self) -> Vec<EmitTarget> {
+        match self.command {
+            Command::Build => EmitTarget::ALL.to_vec(),
+            Command::Check => vec![],
+            Command::Emit(target) => vec![target],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    HelpRequested,
+    Usage(String),
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<CliOptions, CliError> {
+    let command_name = match args.next() {
+        Some(name) => name,
+        None => return Err(CliError::Usage("no command given".to_string())),
+    };
+    if command_name == "-h" || command_name == "--help" {
+        return Err(CliError::HelpRequested);
+    }
+
+    let mut inputs = vec![];
+    let mut out_dir = PathBuf::from("output");
+    let mut target = None;
+    let mut message_format = MessageFormat::Human;
+    while let Some(arg) = args.next() {
+        // both `--flag value` and `--flag=value` are accepted
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Err(CliError::HelpRequested),
+            "--out-dir" => {
+                out_dir = PathBuf::from(flag_value(&flag, inline_value, &mut args)?);
+            }
+            "--target" => {
+                let name = flag_value(&flag, inline_value, &mut args)?;
+                target = Some(EmitTarget::from_name(&name).ok_or_else(|| {
+                    CliError::Usage(format!("unknown target `{}` (expected js or cpp)", name))
+                })?);
+            }
+            "--message-format" => {
+                message_format = match flag_value(&flag, inline_value, &mut args)?.as_str() {
+                    "human" => MessageFormat::Human,
+                    "json" => MessageFormat::Json,
+                    other => {
+                        return Err(CliError::Usage(format!(
+                            "unknown message format `{}` (expected human or json)",
+                            other
+                        )));
+                    }
+                };
+            }
+            _ if flag.starts_with('-') => {
+                return Err(CliError::Usage(format!("unknown option `{}`", arg)));
+            }
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    let command = match command_name.as_str() {
+        "build" => Command::Build,
+        "check" => Command::Check,
+        "emit" => match target {
+            Some(target) => Command::Emit(target),
+            None => return Err(CliError::Usage("emit needs a --target (js or cpp)".to_string())),
+        },
+        other => return Err(CliError::Usage(format!("unknown command `{}`", other))),
+    };
+    if target.is_some() && !matches!(command, Command::Emit(_)) {
+        return Err(CliError::Usage("--target can only be used with the emit command".to_string()));
+    }
+    if inputs.is_empty() {
+        return Err(CliError::Usage("no input files or directories given".to_string()));
+    }
+
+    Ok(CliOptions {
+        command,
+        inputs,
+        out_dir,
+        message_format,
+    })
+}
+
+fn flag_value<I: Iterator<Item = String>>(
+    flag: &str,
+    inline_value: Option<String>,
+    args: &mut I,
+) -> Result<String, CliError> {
+    match inline_value.or_else(|| args.next()) {
+        Some(value) => Ok(value),
+        None => Err(CliError::Usage(format!("{} needs a value", flag))),
+    }
+}
+
+/// expands the inputs given on the command line into the list of source files to compile,
+/// files are taken as is and directories are searched for .bob files
+pub fn collect_source_files(inputs: &[PathBuf]) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for input in inputs {
+        if input.is_dir() {
+            collect_source_files_in_dir(input, &mut files)?;
+        } else {
+            //reading metadata here makes a missing file show up as an io error before any parsing starts
+            fs::metadata(input)?;
+            files.push(input.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn collect_source_files_in_dir(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<PathBuf>>>()?;
+    //read_dir has no guaranteed order, sorting keeps the output (and the error order) stable between runs
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_source_files_in_dir(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == SOURCE_FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// every file is written to out_dir under its stem, so two inputs with the same stem (from different directories) would overwrite each others output.
+/// gives back the first such pair
+pub fn find_output_collision(files: &[PathBuf]) -> Option<(&PathBuf, &PathBuf)> {
+    files.iter().enumerate().find_map(|(index, file)| {
+        files[..index]
+            .iter()
+            .find(|earlier| earlier.file_stem() == file.file_stem())
+            .map(|earlier| (earlier, file))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn test_emit_with_target_and_out_dir() {
+        let options = parse_args(args(&["emit", "--target", "cpp", "--out-dir=build", "input"])).unwrap();
+        assert_eq!(options.command, Command::Emit(EmitTarget::Cpp));
+        assert_eq!(options.out_dir, PathBuf::from("build"));
+        assert_eq!(options.inputs, vec![PathBuf::from("input")]);
+        assert_eq!(options.targets(), vec![EmitTarget::Cpp]);
+        assert_eq!(options.message_format, MessageFormat::Human);
+    }
+
+    #[test]
+    fn test_message_format() {
+        let options = parse_args(args(&["check", "--message-format=json", "input"])).unwrap();
+        assert_eq!(options.message_format, MessageFormat::Json);
+        let options = parse_args(args(&["check", "--message-format", "human", "input"])).unwrap();
+        assert_eq!(options.message_format, MessageFormat::Human);
+        assert!(matches!(parse_args(args(&["check", "--message-format=xml", "input"])), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_check_writes_nothing() {
+        let options = parse_args(args(&["check", "a.bob", "b.bob"])).unwrap();
+        assert_eq!(options.command, Command::Check);
+        assert_eq!(options.inputs.len(), 2);
+        assert!(options.targets().is_empty());
+    }
+
+    #[test]
+    fn test_usage_errors() {
+        assert!(matches!(parse_args(args(&[])), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(args(&["build"])), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(args(&["emit", "input"])), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(args(&["emit", "--target", "go", "input"])), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(args(&["build", "--target", "js", "input"])), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(args(&["build", "--out-dir"])), Err(CliError::Usage(_))));
+        assert_eq!(parse_args(args(&["build", "--help"])).unwrap_err(), CliError::HelpRequested);
+    }
+
+    #[test]
+    fn test_output_collision() {
+        let files = vec![PathBuf::from("a/util.bob"), PathBuf::from("main.bob"), PathBuf::from("b/util.bob")];
+        assert_eq!(find_output_collision(&files), Some((&files[0], &files[2])));
+        assert_eq!(find_output_collision(&files[..2]), None);
+    }
+}
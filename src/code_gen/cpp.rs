@@ -1,17 +1,236 @@
-use crate::parser::function_parser::Function;
+use crate::parser::function_parser::{Function, INFERRED_TYPE};
 use crate::parser::function_parser::Param;
-use crate::parser::code_block::ValidInCodeBlock;
+use crate::parser::code_block::{block_end, match_arms, ForHead, ValidInCodeBlock, VariantPattern};
 use crate::parser::expression::Expression;
+use crate::parser::expression::NumberKind;
 use crate::parser::var_parser::Var;
+use crate::symbol::{sym, Symbol};
+use crate::precedence_order::{is_operator_call, needs_parentheses, precedence, PREFIX_OPERATORS};
 
-type FunctionTokens = Function;
-type ParamTokens = Param;
-type VarTokens = Var;
-type FunctionCallTokens = crate::parser::expression::FunctionCall;
-type FileTokens = crate::file::File;
-type ClassTokens = crate::parser::class_parser::Class;
+type FunctionTokens<'src> = Function<'src>;
+type ParamTokens<'src> = Param<'src>;
+type VarTokens<'src> = Var<'src>;
+type FunctionCallTokens<'src> = crate::parser::expression::FunctionCall<'src>;
+type FileTokens<'src> = crate::file::File<'src>;
+type ClassTokens<'src> = crate::parser::class_parser::Class<'src>;
+type EnumTokens<'src> = crate::parser::enum_parser::Enum<'src>;
 
-impl FunctionTokens {
+/// ranges become an index loop, everything else a range for (with a structured binding for the key and value of a map)
+fn for_head_to_cpp(head: &ForHead) -> String {
+    match (&head.iterable.0, head.names.as_slice()) {
+        (crate::parser::expression::ExpressionPiece::FunctionCall(range), [(name, _)]) if range.name == ".." => format!(
+            "for (int {0} = {1}; {0} < {2}; {0}++)",
+            name,
+            expression_to_cpp(&range.params[0]),
+            expression_to_cpp(&range.params[1])
+        ),
+        (_, [(key, _), (value, _)]) => format!("for (auto& [{}, {}] : {})", key, value, expression_to_cpp(&head.iterable)),
+        _ => format!("for (auto& {} : {})", head.names[0].0, expression_to_cpp(&head.iterable)),
+    }
+}
+
+/// a lambda wrapped in a std::function so it can go anywhere a function type is declared.
+/// it captures by reference so changing a local it uses changes the local itself like in javascript, that means it cant outlive those locals.
+/// expressions dont know how deep they are so the body is indented one level
+fn lambda_to_cpp(function: &Function) -> String {
+    let params = function.params.iter().map(|param| param.generate_cpp_code(0)).collect::<Vec<String>>().join(", ");
+    //without one c++ works it out from the return, same as the checker does for a => function
+    let return_type = match function.return_type.name {
+        INFERRED_TYPE => String::new(),
+        _ => format!(" -> {}", type_to_cpp(&function.return_type)),
+    };
+    format!("std::function([&]({}){} {{\n{}}})", params, return_type, function.function_body_generate_cpp_code(1))
+}
+
+/// a class or enum of the file, they go into the header in the order their fields and payloads need them
+enum TypeDefinition<'a, 'src> {
+    Class(&'a ClassTokens<'src>),
+    Enum(&'a EnumTokens<'src>),
+}
+
+/// the names a type is built out of, its own and those of all its sub types
+fn named_types<'src>(type_: &crate::parser::type_parser::Type_<'src>, names: &mut Vec<&'src str>) {
+    names.push(type_.name);
+    for sub_type in &type_.sub_types {
+        named_types(sub_type, names);
+    }
+}
+
+/// every variant of an enum is a struct of its payload, the enum is a std::variant of them
+fn variant_struct_name(enum_name: Symbol, variant: Symbol) -> String {
+    format!("{}_{}", enum_name, variant)
+}
+
+/// the bindings of an arm are references into the variant the arm got, _ bindings are skipped
+fn bindings_to_cpp(pattern: &VariantPattern, arm: &str, indent: &str) -> String {
+    pattern
+        .bindings
+        .iter()
+        .zip(&pattern.payload_names)
+        .filter(|((binding, _), _)| *binding != "_")
+        .map(|((binding, _), payload_name)| format!("{}auto& {} = {}->{};\n", indent, binding, arm, payload_name))
+        .collect()
+}
+
+fn parenthesize(code: String, needed: bool) -> String {
+    if needed {
+        format!("({})", code)
+    } else {
+        code
+    }
+}
+
+/// every node turns its types into c++ through here, arrays and maps become the std containers and optional types a std::optional
+fn type_to_cpp(type_: &crate::parser::type_parser::Type_) -> String {
+    let cpp_type = match type_.name {
+        "int" => "int".to_string(),
+        "string" => "std::string".to_string(),
+        //a char is a whole code point, a c++ char is only a byte
+        "char" => "char32_t".to_string(),
+        "bool" => "bool".to_string(),
+        "void" => "void".to_string(),
+        "float" => "float".to_string(),
+        "double" => "double".to_string(),
+        "array" => format!("std::vector<{}>", type_to_cpp(&type_.sub_types[0])),
+        "map" => format!("std::map<{}, {}>", type_to_cpp(&type_.sub_types[0]), type_to_cpp(&type_.sub_types[1])),
+        //function<(params), return>
+        "function" if type_.sub_types.len() == 2 => {
+            let params = type_.sub_types[0].sub_types.iter().map(type_to_cpp).collect::<Vec<String>>();
+            format!("std::function<{}({})>", type_to_cpp(&type_.sub_types[1]), params.join(", "))
+        }
+        _ => type_.name.to_string(),
+    };
+    match type_.is_optional {
+        true => format!("std::optional<{}>", cpp_type),
+        false => cpp_type,
+    }
+}
+
+/// every node turns its expressions into c++ through here so they all come out the same
+fn expression_to_cpp(expr: &Expression) -> String {
+    match &expr.0 {
+        crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
+        crate::parser::expression::ExpressionPiece::StringLiteral(value) => string_to_cpp(value),
+        crate::parser::expression::ExpressionPiece::NumberLiteral(text, kind) => number_to_cpp(text, *kind),
+        crate::parser::expression::ExpressionPiece::BoolLiteral(value) => value.to_string(),
+        crate::parser::expression::ExpressionPiece::CharLiteral(value) => char_to_cpp(*value),
+        crate::parser::expression::ExpressionPiece::NullLiteral => "std::nullopt".to_string(),
+        crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
+        crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
+            // Check if this is a control flow function call that should be handled specially
+            if func_call.name == "while" || func_call.name == "if" {
+                // For control flow, just return the condition without the function name
+                if func_call.params.len() == 1 {
+                    expression_to_cpp(&func_call.params[0])
+                } else {
+                    func_call.generate_cpp_code(0)
+                }
+            } else {
+                func_call.generate_cpp_code(0)
+            }
+        }
+        crate::parser::expression::ExpressionPiece::MemberAccess(access) => {
+            let object = parenthesize(expression_to_cpp(&access.object), is_operator_call(&access.object));
+            //members a method uses without a prefix come from the checker as accesses on this, which is a pointer in c++
+            let dot = match access.object.0 {
+                crate::parser::expression::ExpressionPiece::Variable(name) if name == sym::THIS => "->",
+                _ => ".",
+            };
+            match &access.args {
+                Some(args) => {
+                    let args = args.iter().map(expression_to_cpp).collect::<Vec<String>>().join(", ");
+                    format!("{}{}{}({})", object, dot, access.name, args)
+                }
+                None => format!("{}{}{}", object, dot, access.name),
+            }
+        }
+        crate::parser::expression::ExpressionPiece::Index(object, index) | crate::parser::expression::ExpressionPiece::MapIndex(object, index) => {
+            let object_str = parenthesize(expression_to_cpp(object), is_operator_call(object));
+            format!("{}[{}]", object_str, expression_to_cpp(index))
+        }
+        //brace initializers, the vector or map they build comes from the type they are assigned to
+        crate::parser::expression::ExpressionPiece::ArrayLiteral(elements) => {
+            format!("{{{}}}", elements.iter().map(expression_to_cpp).collect::<Vec<String>>().join(", "))
+        }
+        crate::parser::expression::ExpressionPiece::MapLiteral(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("{{{}, {}}}", expression_to_cpp(key), expression_to_cpp(value)))
+                .collect::<Vec<String>>();
+            format!("{{{}}}", entries.join(", "))
+        }
+        //the checker already put the fields in the order the constructor takes them
+        crate::parser::expression::ExpressionPiece::Construction(construction) => {
+            let args = construction.fields.iter().map(|(_, _, value)| expression_to_cpp(value));
+            format!("{}({})", construction.class_name, args.collect::<Vec<String>>().join(", "))
+        }
+        crate::parser::expression::ExpressionPiece::Lambda(function) => lambda_to_cpp(function),
+        crate::parser::expression::ExpressionPiece::VariantValue(value) => {
+            let payload = value.payload.iter().map(|(_, value)| expression_to_cpp(value));
+            format!(
+                "{}({}{{{}}})",
+                value.enum_name,
+                variant_struct_name(value.enum_name, value.variant),
+                payload.collect::<Vec<String>>().join(", ")
+            )
+        }
+        crate::parser::expression::ExpressionPiece::Placeholder(_) => "nullptr".to_string(),
+    }
+}
+
+/// the value is already unescaped so everything that cant go in a c++ string as is gets escaped again,
+/// non ascii chars stay as they are since the generated files are utf-8 just like the source
+fn string_to_cpp(value: &str) -> String {
+    let mut res = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            '\r' => res.push_str("\\r"),
+            //octal and not \x bc \x keeps eating hex digits, so \x1 followed by a b would be read as \x1b
+            c if c.is_ascii_control() => res.push_str(&format!("\\{:03o}", c as u32)),
+            c if c.is_control() => res.push_str(&format!("\\U{:08x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+/// chars are char32_t in c++, so every literal gets the U prefix to have that type too
+fn char_to_cpp(value: char) -> String {
+    match value {
+        '\'' => "U'\\''".to_string(),
+        '\\' => "U'\\\\'".to_string(),
+        '\n' => "U'\\n'".to_string(),
+        '\t' => "U'\\t'".to_string(),
+        '\r' => "U'\\r'".to_string(),
+        c if c.is_ascii_control() => format!("U'\\{:03o}'", c as u32),
+        c if c.is_ascii() => format!("U'{}'", c),
+        c => format!("U'\\U{:08x}'", c as u32),
+    }
+}
+
+fn number_to_cpp(text: &str, kind: NumberKind) -> String {
+    let digits = kind.digits(text);
+    match kind {
+        NumberKind::Int { radix: 16 } => format!("0x{}", digits),
+        //c++ has no 0o, a leading 0 is how it writes octal
+        NumberKind::Int { radix: 8 } => format!("0{}", digits),
+        NumberKind::Int { radix: 2 } => format!("0b{}", digits),
+        NumberKind::Int { .. } => strip_leading_zeros(&digits).to_string(),
+        NumberKind::Float => digits,
+    }
+}
+
+fn strip_leading_zeros(digits: &str) -> &str {
+    let stripped = digits.trim_start_matches('0');
+    if stripped.is_empty() { "0" } else { stripped }
+}
+
+impl FunctionTokens<'_> {
     pub fn function_header_generate_cpp_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
         let param_list = self.params.iter()
@@ -28,52 +247,101 @@ impl FunctionTokens {
 
     fn cpp_generate_body_from_tokens(&self, tokens: &[ValidInCodeBlock], depth: usize) -> String {
         let mut output = String::new();
+        let indent = "    ".repeat(depth);
         let mut i = 0;
         while i < tokens.len() {
             match &tokens[i] {
                 ValidInCodeBlock::WhileStartMarker => {
                     // Next should be condition, then body, then JumpIndex
                     if let Some(ValidInCodeBlock::Expression(cond)) = tokens.get(i + 1) {
-                        output.push_str(&format!("{}while ({}) {{\n", "    ".repeat(depth), self.expression_to_cpp(cond)));
-                        // Find the body (from i+2 to JumpIndex)
-                        let mut j = i + 2;
-                        let mut body_tokens = Vec::new();
-                        while j < tokens.len() {
-                            if let ValidInCodeBlock::JumpIndex(_) = tokens[j] {
-                                break;
-                            }
-                            body_tokens.push(tokens[j].clone());
-                            j += 1;
-                        }
-                        output.push_str(&self.cpp_generate_body_from_tokens(&body_tokens, depth + 1));
-                        output.push_str(&format!("{}}}\n", "    ".repeat(depth)));
-                        i = j + 1; // Skip past JumpIndex
+                        output.push_str(&format!("{}while ({}) {{\n", indent, expression_to_cpp(cond)));
+                        let end = block_end(tokens, i);
+                        output.push_str(&self.cpp_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                        output.push_str(&format!("{}}}\n", indent));
+                        i = end + 1; // Skip past JumpIndex
                         continue;
                     }
                 }
+                ValidInCodeBlock::ForStartMarker(head) => {
+                    output.push_str(&format!("{}{} {{\n", indent, for_head_to_cpp(head)));
+                    let end = block_end(tokens, i);
+                    output.push_str(&self.cpp_generate_body_from_tokens(&tokens[i + 1..end], depth + 1));
+                    output.push_str(&format!("{}}}\n", indent));
+                    i = end + 1;
+                    continue;
+                }
                 ValidInCodeBlock::IfStartMarker => {
                     // Next should be condition, then body, then JumpIndex
                     if let Some(ValidInCodeBlock::Expression(cond)) = tokens.get(i + 1) {
-                        let condition_str = self.expression_to_cpp(cond);
+                        let condition_str = expression_to_cpp(cond);
                         // Check if the condition is just a variable named "if" (parser error)
                         let condition = if condition_str == "if" { "true" } else { &condition_str };
-                        output.push_str(&format!("{}if ({}) {{\n", "    ".repeat(depth), condition));
-                        // Find the body (from i+2 to JumpIndex)
-                        let mut j = i + 2;
-                        let mut body_tokens = Vec::new();
-                        while j < tokens.len() {
-                            if let ValidInCodeBlock::JumpIndex(_) = tokens[j] {
-                                break;
+                        output.push_str(&format!("{}if ({}) {{\n", indent, condition));
+                        let end = block_end(tokens, i);
+                        output.push_str(&self.cpp_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                        i = end + 1; // Skip past JumpIndex
+                        // the else ifs and the else of this if follow right after it
+                        loop {
+                            match (tokens.get(i), tokens.get(i + 1)) {
+                                (Some(ValidInCodeBlock::ElseIfStartMarker), Some(ValidInCodeBlock::Expression(cond))) => {
+                                    output.push_str(&format!("{}}} else if ({}) {{\n", indent, expression_to_cpp(cond)));
+                                    let end = block_end(tokens, i);
+                                    output.push_str(&self.cpp_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                                    i = end + 1;
+                                }
+                                (Some(ValidInCodeBlock::ElseStartMarker), _) => {
+                                    output.push_str(&format!("{}}} else {{\n", indent));
+                                    let end = block_end(tokens, i);
+                                    output.push_str(&self.cpp_generate_body_from_tokens(&tokens[i + 1..end], depth + 1));
+                                    i = end + 1;
+                                    break;
+                                }
+                                _ => break,
                             }
-                            body_tokens.push(tokens[j].clone());
-                            j += 1;
                         }
-                        output.push_str(&self.cpp_generate_body_from_tokens(&body_tokens, depth + 1));
-                        output.push_str(&format!("{}}}\n", "    ".repeat(depth)));
-                        i = j + 1; // Skip past JumpIndex
+                        output.push_str(&format!("{}}}\n", indent));
                         continue;
                     }
                 }
+                //the subject is only worked out once, the arms check which variant it holds in order and _ is the else
+                ValidInCodeBlock::MatchStartMarker(subject) => {
+                    let arm_indent = "    ".repeat(depth + 1);
+                    output.push_str(&format!("{}{{\n{}auto&& _match = {};\n", indent, arm_indent, expression_to_cpp(subject)));
+                    let arms = match_arms(tokens, i);
+                    for (index, (pattern, body)) in arms.iter().enumerate() {
+                        let else_ = if index == 0 { "" } else { "} else " };
+                        let opener = match pattern {
+                            //only an arm with bindings needs to get at the payload
+                            Some(pattern) if !pattern.bindings.is_empty() => format!(
+                                "{}if (auto* _arm{} = std::get_if<{}>(&_match)) {{",
+                                else_,
+                                index,
+                                variant_struct_name(pattern.enum_name, pattern.variant)
+                            ),
+                            Some(pattern) => format!(
+                                "{}if (std::holds_alternative<{}>(_match)) {{",
+                                else_,
+                                variant_struct_name(pattern.enum_name, pattern.variant)
+                            ),
+                            None => format!("{}{{", else_),
+                        };
+                        output.push_str(&format!("{}{}\n", arm_indent, opener));
+                        if let Some(pattern) = pattern {
+                            output.push_str(&bindings_to_cpp(pattern, &format!("_arm{}", index), &"    ".repeat(depth + 2)));
+                        }
+                        output.push_str(&self.cpp_generate_body_from_tokens(&tokens[body.clone()], depth + 2));
+                        //arms after _ can never run
+                        if pattern.is_none() {
+                            break;
+                        }
+                    }
+                    if !arms.is_empty() {
+                        output.push_str(&format!("{}}}\n", arm_indent));
+                    }
+                    output.push_str(&format!("{}}}\n", indent));
+                    i = block_end(tokens, i) + 1;
+                    continue;
+                }
                 ValidInCodeBlock::JumpIndex(_) => {
                     // Should be handled by the above logic, just skip
                     i += 1;
@@ -92,7 +360,7 @@ impl FunctionTokens {
         let indent = "    ".repeat(depth);
         match statement {
             ValidInCodeBlock::Expression(expr) => {
-                format!("{}{};\n", indent, self.expression_to_cpp(expr))
+                format!("{}{};\n", indent, expression_to_cpp(expr))
             }
             ValidInCodeBlock::Var(var) => {
                 format!("{}{};\n", indent, var.generate_cpp_code(depth))
@@ -106,54 +374,44 @@ impl FunctionTokens {
             ValidInCodeBlock::IfStartMarker => {
                 format!("{}if ", indent)
             }
+            ValidInCodeBlock::ElseIfStartMarker => {
+                format!("{}else if ", indent)
+            }
+            ValidInCodeBlock::ElseStartMarker => {
+                format!("{}else ", indent)
+            }
+            ValidInCodeBlock::ForStartMarker(head) => {
+                format!("{}{} ", indent, for_head_to_cpp(head))
+            }
+            ValidInCodeBlock::Return(Some(value), _) => {
+                format!("{}return {};\n", indent, expression_to_cpp(value))
+            }
+            ValidInCodeBlock::Return(None, _) => {
+                format!("{}return;\n", indent)
+            }
+            ValidInCodeBlock::Break(_) => {
+                format!("{}break;\n", indent)
+            }
+            ValidInCodeBlock::Continue(_) => {
+                format!("{}continue;\n", indent)
+            }
             ValidInCodeBlock::JumpIndex(_) => {
                 String::new() // Handle jump logic if needed
             }
+            //matches are written out whole by cpp_generate_body_from_tokens
+            ValidInCodeBlock::MatchStartMarker(_) | ValidInCodeBlock::MatchArmStartMarker(..) => String::new(),
             ValidInCodeBlock::HeadEndAndBodyStartMarker => {
                 format!("{} {{\n", indent)
             }
         }
     }
-    
-    fn expression_to_cpp(&self, expr: &Expression) -> String {
-        // Convert Expression to C++ code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                // Check if this is a control flow function call that should be handled specially
-                if func_call.name == "while" || func_call.name == "if" {
-                    // For control flow, just return the condition without the function name
-                    if func_call.params.len() == 1 {
-                        self.expression_to_cpp(&func_call.params[0])
-                    } else {
-                        func_call.generate_cpp_code(0)
-                    }
-                } else {
-                    func_call.generate_cpp_code(0)
-                }
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "nullptr".to_string(),
-        }
-    }
 
     fn cpp_type_name(&self, type_: &crate::parser::type_parser::Type_) -> String {
-        match type_.name {
-            "int" => "int".to_string(),
-            "string" => "std::string".to_string(),
-            "char" => "char".to_string(),
-            "bool" => "bool".to_string(),
-            "void" => "void".to_string(),
-            "float" => "float".to_string(),
-            "double" => "double".to_string(),
-            _ => type_.name.to_string(),
-        }
+        type_to_cpp(type_)
     }
 }
 
-impl ParamTokens {
+impl ParamTokens<'_> {
     pub fn generate_cpp_code(&self, _depth: usize) -> String {
         let type_name = self.cpp_type_name(&self.type_);
         match &self.default_value.0 {
@@ -169,89 +427,46 @@ impl ParamTokens {
     }
 
     fn cpp_type_name(&self, type_: &crate::parser::type_parser::Type_) -> String {
-        match type_.name {
-            "int" => "int".to_string(),
-            "string" => "std::string".to_string(),
-            "char" => "char".to_string(),
-            "bool" => "bool".to_string(),
-            "void" => "void".to_string(),
-            "float" => "float".to_string(),
-            "double" => "double".to_string(),
-            _ => type_.name.to_string(),
-        }
+        type_to_cpp(type_)
     }
 }
 
-impl VarTokens {
+impl VarTokens<'_> {
     pub fn generate_cpp_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
         let type_name = self.cpp_type_name(&self.type_);
-        let value_str = self.expression_to_cpp(&self.default_value);
+        let value_str = expression_to_cpp(&self.default_value);
         format!("{}{} {} = {}", indent, type_name, self.name, value_str)
     }
-    
-    fn expression_to_cpp(&self, expr: &Expression) -> String {
-        // Convert Expression to C++ code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_cpp_code(0)
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "nullptr".to_string(),
-        }
-    }
 
     fn cpp_type_name(&self, type_: &crate::parser::type_parser::Type_) -> String {
-        match type_.name {
-            "int" => "int".to_string(),
-            "string" => "std::string".to_string(),
-            "char" => "char".to_string(),
-            "bool" => "bool".to_string(),
-            "void" => "void".to_string(),
-            "float" => "float".to_string(),
-            "double" => "double".to_string(),
-            _ => type_.name.to_string(),
-        }
+        type_to_cpp(type_)
     }
 }
 
-impl FunctionCallTokens {
+impl FunctionCallTokens<'_> {
     pub fn generate_cpp_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
-        let operator_names = ["=", "+", "-", "*", "/", "%", ">", "<", ">=", "<=", "==", "!=", "&&", "||", "^", "|", "&"]; // Add more as needed
-        if operator_names.contains(&self.name) && self.params.len() == 2 {
+        if precedence(&self.name).is_some() && self.params.len() == 2 {
             // Infix notation for binary operators
-            let left = self.expression_to_cpp(&self.params[0]);
-            let right = self.expression_to_cpp(&self.params[1]);
+            let left = parenthesize(expression_to_cpp(&self.params[0]), needs_parentheses(&self.name, &self.params[0], false));
+            let right = parenthesize(expression_to_cpp(&self.params[1]), needs_parentheses(&self.name, &self.params[1], true));
             format!("{}{} {} {}", indent, left, self.name, right)
+        } else if PREFIX_OPERATORS.contains(&self.name.as_str()) && self.params.len() == 1 {
+            //- -a has to stay apart or it turns into a decrement
+            let operand = parenthesize(expression_to_cpp(&self.params[0]), is_operator_call(&self.params[0]));
+            format!("{}{}{}", indent, self.name, operand)
         } else {
             let params = self.params.iter()
-                .map(|param| self.expression_to_cpp(param))
+                .map(|param| expression_to_cpp(param))
                 .collect::<Vec<String>>()
                 .join(", ");
             format!("{}{}({})", indent, self.name, params)
         }
     }
-    
-    fn expression_to_cpp(&self, expr: &Expression) -> String {
-        // Convert Expression to C++ code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_cpp_code(0)
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "nullptr".to_string(),
-        }
-    }
 }
 
-impl FileTokens {
+impl FileTokens<'_> {
     pub fn generate_cpp_header_file(&self) -> String {
         let mut output = String::new();
         
@@ -264,11 +479,23 @@ impl FileTokens {
         output.push_str("#include <string>\n");
         output.push_str("#include <vector>\n");
         output.push_str("#include <map>\n");
+        output.push_str("#include <functional>\n");
+        output.push_str("#include <variant>\n");
+        output.push_str("#include <optional>\n");
         output.push_str("\n");
-        
-        // Generate class declarations
+
+        // Declare every class up front so methods can take and return classes that come later
         for class in &self.classes {
-            output.push_str(&class.generate_cpp_header(0));
+            output.push_str(&format!("class {};\n", class.name));
+        }
+        output.push('\n');
+
+        // Generate enum and class declarations
+        for definition in self.type_definitions_in_dependency_order() {
+            match definition {
+                TypeDefinition::Enum(_enum) => output.push_str(&_enum.generate_cpp_header(0)),
+                TypeDefinition::Class(class) => output.push_str(&class.generate_cpp_header(0)),
+            }
             output.push('\n');
         }
         
@@ -293,6 +520,50 @@ impl FileTokens {
         output
     }
 
+    /// fields and payloads hold their values directly, so c++ needs the whole definition of their type before the class or enum that has them.
+    /// types that (wrongly) hold each other stay in the order they were written in
+    fn type_definitions_in_dependency_order(&self) -> Vec<TypeDefinition<'_, '_>> {
+        let mut ordered = vec![];
+        let mut visited = vec![];
+        for name in self.enums.iter().map(|_enum| _enum.name).chain(self.classes.iter().map(|class| class.name)) {
+            self.visit_type_definition(name.as_str(), &mut visited, &mut ordered);
+        }
+        ordered
+    }
+
+    fn visit_type_definition<'a>(&'a self, name: &str, visited: &mut Vec<Symbol<'a>>, ordered: &mut Vec<TypeDefinition<'a, 'a>>) {
+        let definition = match (self.enums.iter().find(|_enum| _enum.name == name), self.classes.iter().find(|class| class.name == name)) {
+            (Some(_enum), _) => TypeDefinition::Enum(_enum),
+            (None, Some(class)) => TypeDefinition::Class(class),
+            (None, None) => return,
+        };
+        let own_name = match definition {
+            TypeDefinition::Enum(_enum) => _enum.name,
+            TypeDefinition::Class(class) => class.name,
+        };
+        if visited.contains(&own_name) {
+            return;
+        }
+        visited.push(own_name);
+        let mut used = vec![];
+        match definition {
+            TypeDefinition::Enum(_enum) => {
+                for (_, type_) in _enum.variants.iter().flat_map(|variant| variant.payload.iter()) {
+                    named_types(type_, &mut used);
+                }
+            }
+            TypeDefinition::Class(class) => {
+                for field in &class.fields {
+                    named_types(&field.type_, &mut used);
+                }
+            }
+        }
+        for used_name in used {
+            self.visit_type_definition(used_name, visited, ordered);
+        }
+        ordered.push(definition);
+    }
+
     pub fn generate_cpp_code(&self, depth: usize) -> String {
         let mut output = String::new();
 
@@ -324,7 +595,29 @@ impl FileTokens {
     }
 }
 
-impl ClassTokens {
+impl EnumTokens<'_> {
+    pub fn generate_cpp_header(&self, depth: usize) -> String {
+        let indent = "    ".repeat(depth);
+        let mut output = String::new();
+        for variant in &self.variants {
+            let fields = variant
+                .payload
+                .iter()
+                .map(|(name, type_)| format!(" {} {};", type_to_cpp(type_), name))
+                .collect::<String>();
+            output.push_str(&format!("{}struct {} {{{} }};\n", indent, variant_struct_name(self.name, variant.name), fields));
+        }
+        //std::variant needs at least one alternative
+        let alternatives = match self.variants.is_empty() {
+            true => vec!["std::monostate".to_string()],
+            false => self.variants.iter().map(|variant| variant_struct_name(self.name, variant.name)).collect(),
+        };
+        output.push_str(&format!("{}using {} = std::variant<{}>;\n", indent, self.name, alternatives.join(", ")));
+        output
+    }
+}
+
+impl ClassTokens<'_> {
     pub fn generate_cpp_header(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
         let mut output = format!("{}class {} {{\n", indent, self.name);
@@ -343,6 +636,9 @@ impl ClassTokens {
         // Add constructor declaration
         let constructor_indent = "    ".repeat(depth + 2);
         output.push_str(&format!("{}    {}();\n", constructor_indent, self.name));
+        if !self.fields.is_empty() {
+            output.push_str(&format!("{}    {}({});\n", constructor_indent, self.name, self.field_params()));
+        }
         
         // Add method declarations (just signatures, no bodies)
         for method in &self.methods {
@@ -360,6 +656,15 @@ impl ClassTokens {
         output
     }
 
+    /// the params of the constructor that takes every field, named after the fields
+    fn field_params(&self) -> String {
+        self.fields
+            .iter()
+            .map(|field| format!("{} {}", type_to_cpp(&field.type_), field.name))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
     pub fn generate_cpp_implementations(&self, depth: usize) -> String {
         let mut output = String::new();
         
@@ -375,12 +680,26 @@ impl ClassTokens {
                     // Don't initialize if no default value
                 }
                 _ => {
-                    output.push_str(&format!("{}    {} = {};\n", init_indent, field.name, self.expression_to_cpp(&field.default_value)));
+                    output.push_str(&format!("{}    {} = {};\n", init_indent, field.name, expression_to_cpp(&field.default_value)));
                 }
             }
         }
         
         output.push_str(&format!("{}}}\n", indent));
+
+        // a construction passes every field in order
+        if !self.fields.is_empty() {
+            let initializers = self.fields.iter().map(|field| format!("{}({})", field.name, field.name));
+            output.push_str(&format!(
+                "{}{}::{}({}) : {} {{\n",
+                indent,
+                self.name,
+                self.name,
+                self.field_params(),
+                initializers.collect::<Vec<String>>().join(", ")
+            ));
+            output.push_str(&format!("{}}}\n", indent));
+        }
         
         // Add method implementations in the style ReturnType ClassName::methodName()
         for method in &self.methods {
@@ -397,31 +716,121 @@ impl ClassTokens {
         output.push('\n');
         output
     }
-    
-    fn expression_to_cpp(&self, expr: &Expression) -> String {
-        // Convert Expression to C++ code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_cpp_code(0)
+
+    fn cpp_type_name(&self, type_: &crate::parser::type_parser::Type_) -> String {
+        type_to_cpp(type_)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file::File;
+    use crate::project_basic_utils::tokenizer::Tokenizer;
+    use std::fs;
+    use std::process::Command;
+
+    /// compiles the code to c++, builds it with g++ together with the driver (which has the main) and gives back what it printed.
+    /// every test needs its own name since they build next to each other in the temp dir
+    fn run_cpp(name: &str, code: &str, driver: &str) -> String {
+        let mut file = File::new(Tokenizer::new(file!(), line!() as usize, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        assert!(!file.tokenizer.diagnostics.has_errors(), "{}", file.tokenizer.render_diagnostics());
+        let dir = std::env::temp_dir().join(format!("bob_cpp_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let base_name = file.get_base_file_name();
+        let header = file.generate_cpp_header_file();
+        let source = format!("{}\n{}", file.generate_cpp_code(0), driver);
+        fs::write(dir.join(format!("{}.hpp", base_name)), &header).unwrap();
+        fs::write(dir.join(format!("{}.cpp", base_name)), &source).unwrap();
+        let binary = dir.join(name);
+        let compiled = Command::new("g++")
+            .arg("-std=c++17")
+            .arg("-o")
+            .arg(&binary)
+            .arg(dir.join(format!("{}.cpp", base_name)))
+            .output()
+            .expect("g++ has to be installed to run the c++ tests");
+        assert!(compiled.status.success(), "{}\n{}\n{}", header, source, String::from_utf8_lossy(&compiled.stderr));
+        let output = Command::new(&binary).output().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_optionals_and_chars() {
+        let code = "function same(char c) char {
+                return c
             }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "nullptr".to_string(),
-        }
+
+            function summary() string {
+                let int? missing = null
+                let int? present = 5
+                let char accent = 'é'
+                let char quote = '\\''
+                if missing == null && present != null && same(accent) == accent && quote != accent {
+                    return \"ok\"
+                }
+                return \"wrong\"
+            }
+            ";
+        let driver = "int main() {
+    std::cout << summary() << (same(U'\\u00e9') == U'\\U000000e9') << \"\\n\";
+}";
+        assert_eq!(run_cpp("optionals_and_chars", code, driver), "ok1\n");
     }
 
-    fn cpp_type_name(&self, type_: &crate::parser::type_parser::Type_) -> String {
-        match type_.name {
-            "int" => "int".to_string(),
-            "string" => "std::string".to_string(),
-            "char" => "char".to_string(),
-            "bool" => "bool".to_string(),
-            "void" => "void".to_string(),
-            "float" => "float".to_string(),
-            "double" => "double".to_string(),
-            _ => type_.name.to_string(),
-        }
+    #[test]
+    fn test_lambdas_change_the_locals_they_use() {
+        let code = "function total(array<int> numbers) int {
+                let int counter = 0
+                let function<(int), void> add = function(int a) void {
+                    counter += a
+                }
+                for number in numbers {
+                    add(number)
+                }
+                return counter
+            }
+            ";
+        let driver = "int main() {
+    std::cout << total({1, 2, 3}) << \"\\n\";
+}";
+        assert_eq!(run_cpp("lambdas_change_the_locals_they_use", code, driver), "6\n");
+    }
+
+    #[test]
+    fn test_types_come_before_the_types_that_hold_them() {
+        let code = "class Parking {
+                Slot slot
+            }
+            enum Slot {
+                Taken(Person person),
+                Free
+            }
+            class Person {
+                int id
+            }
+            function owner(Parking parking) int {
+                match parking.slot {
+                    Slot.Taken(person) => {
+                        return person.id
+                    }
+                    Slot.Free => {
+                        return 0
+                    }
+                }
+                return 0
+            }
+            function park() int {
+                let Parking parking = Parking{ slot: Slot.Taken(Person{ id: 7 }) }
+                return owner(parking)
+            }
+            ";
+        let driver = "int main() {
+    std::cout << park() << \"\\n\";
+}";
+        assert_eq!(run_cpp("types_come_before_the_types_that_hold_them", code, driver), "7\n");
     }
 }
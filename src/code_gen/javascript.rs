@@ -1,17 +1,186 @@
 use crate::parser::function_parser::Function;
 use crate::parser::function_parser::Param;
-use crate::parser::code_block::ValidInCodeBlock;
+use crate::parser::code_block::{block_end, match_arms, ForHead, ValidInCodeBlock, VariantPattern};
 use crate::parser::expression::Expression;
+use crate::parser::expression::NumberKind;
 use crate::parser::var_parser::Var;
+use crate::precedence_order::{is_operator_call, needs_parentheses, precedence, ASSIGNMENT_OPERATORS, PREFIX_OPERATORS};
 
-type FunctionTokens = Function;
-type ParamTokens = Param;
-type VarTokens = Var;
-type FunctionCallTokens = crate::parser::expression::FunctionCall;
-type FileTokens = crate::file::File;
-type ClassTokens = crate::parser::class_parser::Class;
+type FunctionTokens<'src> = Function<'src>;
+type ParamTokens<'src> = Param<'src>;
+type VarTokens<'src> = Var<'src>;
+type FunctionCallTokens<'src> = crate::parser::expression::FunctionCall<'src>;
+type FileTokens<'src> = crate::file::File<'src>;
+type ClassTokens<'src> = crate::parser::class_parser::Class<'src>;
 
-impl FunctionTokens {
+/// ranges count up with a plain for, everything else uses for of, for a Map that gives back its keys with their values
+fn for_head_to_javascript(head: &ForHead) -> String {
+    match (&head.iterable.0, head.names.as_slice()) {
+        (crate::parser::expression::ExpressionPiece::FunctionCall(range), [(name, _)]) if range.name == ".." => format!(
+            "for (let {0} = {1}; {0} < {2}; {0}++)",
+            name,
+            expression_to_javascript(&range.params[0]),
+            expression_to_javascript(&range.params[1])
+        ),
+        (_, [(key, _), (value, _)]) => {
+            format!("for (const [{}, {}] of {})", key, value, expression_to_javascript(&head.iterable))
+        }
+        _ => format!("for (const {} of {})", head.names[0].0, expression_to_javascript(&head.iterable)),
+    }
+}
+
+/// an arrow function, a => function keeps its short form. expressions dont know how deep they are so a block body is indented one level
+fn lambda_to_javascript(function: &Function) -> String {
+    let params = function.params.iter().map(|param| param.generate_javascript_code(0)).collect::<Vec<String>>().join(", ");
+    match function.body.as_slice() {
+        //an object right after => would be read as a block
+        [ValidInCodeBlock::Return(Some(value), _)] => {
+            let is_object = matches!(value.0, crate::parser::expression::ExpressionPiece::VariantValue(_));
+            format!("({}) => {}", params, parenthesize(expression_to_javascript(value), is_object))
+        }
+        _ => format!("({}) => {{\n{}}}", params, function.function_body_generate_javascript_code(1)),
+    }
+}
+
+/// the bindings of an arm read their part of the payload out of the matched object, _ bindings are skipped
+fn bindings_to_javascript(pattern: &VariantPattern, indent: &str) -> String {
+    pattern
+        .bindings
+        .iter()
+        .zip(&pattern.payload_names)
+        .filter(|((binding, _), _)| *binding != "_")
+        .map(|((binding, _), payload_name)| format!("{}const {} = _match.{};\n", indent, binding, payload_name))
+        .collect()
+}
+
+/// a Map is written through set, a compound assignment like += reads the old value with get first
+fn map_assignment_to_javascript(operator: &str, map: &Expression, key: &Expression, value: &Expression) -> String {
+    let map_str = parenthesize(expression_to_javascript(map), is_operator_call(map));
+    let key_str = expression_to_javascript(key);
+    let value_str = match operator.strip_suffix('=').filter(|operator| !operator.is_empty()) {
+        Some(operator) => {
+            let value_str = parenthesize(expression_to_javascript(value), needs_parentheses(operator, value, true));
+            format!("{}.get({}) {} {}", map_str, key_str, operator, value_str)
+        }
+        None => expression_to_javascript(value),
+    };
+    format!("{}.set({}, {})", map_str, key_str, value_str)
+}
+
+fn parenthesize(code: String, needed: bool) -> String {
+    if needed {
+        format!("({})", code)
+    } else {
+        code
+    }
+}
+
+/// every node turns its expressions into javascript through here so they all come out the same
+fn expression_to_javascript(expr: &Expression) -> String {
+    match &expr.0 {
+        crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
+        crate::parser::expression::ExpressionPiece::StringLiteral(value) => string_to_javascript(value),
+        crate::parser::expression::ExpressionPiece::NumberLiteral(text, kind) => number_to_javascript(text, *kind),
+        crate::parser::expression::ExpressionPiece::BoolLiteral(value) => value.to_string(),
+        //js has no char type, a one char string is the closest thing
+        crate::parser::expression::ExpressionPiece::CharLiteral(value) => string_to_javascript(&value.to_string()),
+        crate::parser::expression::ExpressionPiece::NullLiteral => "null".to_string(),
+        crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
+        crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
+            // Check if this is a control flow function call that should be handled specially
+            if func_call.name == "while" || func_call.name == "if" {
+                // For control flow, just return the condition without the function name
+                if func_call.params.len() == 1 {
+                    expression_to_javascript(&func_call.params[0])
+                } else {
+                    func_call.generate_javascript_code(0)
+                }
+            } else {
+                func_call.generate_javascript_code(0)
+            }
+        }
+        crate::parser::expression::ExpressionPiece::MemberAccess(access) => {
+            let object = parenthesize(expression_to_javascript(&access.object), is_operator_call(&access.object));
+            match &access.args {
+                Some(args) => {
+                    let args = args.iter().map(expression_to_javascript).collect::<Vec<String>>().join(", ");
+                    format!("{}.{}({})", object, access.name, args)
+                }
+                None => format!("{}.{}", object, access.name),
+            }
+        }
+        crate::parser::expression::ExpressionPiece::Index(object, index) => {
+            let object_str = parenthesize(expression_to_javascript(object), is_operator_call(object));
+            format!("{}[{}]", object_str, expression_to_javascript(index))
+        }
+        crate::parser::expression::ExpressionPiece::MapIndex(map, key) => {
+            let map_str = parenthesize(expression_to_javascript(map), is_operator_call(map));
+            format!("{}.get({})", map_str, expression_to_javascript(key))
+        }
+        crate::parser::expression::ExpressionPiece::ArrayLiteral(elements) => {
+            format!("[{}]", elements.iter().map(expression_to_javascript).collect::<Vec<String>>().join(", "))
+        }
+        //a Map keeps its keys as they are, a plain object would turn them all into strings
+        crate::parser::expression::ExpressionPiece::MapLiteral(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| format!("[{}, {}]", expression_to_javascript(key), expression_to_javascript(value)))
+                .collect::<Vec<String>>();
+            format!("new Map([{}])", entries.join(", "))
+        }
+        //the checker already put the fields in the order the constructor takes them
+        crate::parser::expression::ExpressionPiece::Construction(construction) => {
+            let args = construction.fields.iter().map(|(_, _, value)| expression_to_javascript(value));
+            format!("new {}({})", construction.class_name, args.collect::<Vec<String>>().join(", "))
+        }
+        crate::parser::expression::ExpressionPiece::Lambda(function) => lambda_to_javascript(function),
+        //an enum value is an object tagged with the name of its variant
+        crate::parser::expression::ExpressionPiece::VariantValue(value) => {
+            let mut entries = vec![format!("tag: {}", string_to_javascript(&value.variant))];
+            entries.extend(value.payload.iter().map(|(name, value)| format!("{}: {}", name, expression_to_javascript(value))));
+            format!("{{{}}}", entries.join(", "))
+        }
+        crate::parser::expression::ExpressionPiece::Placeholder(_) => "null".to_string(),
+    }
+}
+
+/// the value is already unescaped so everything that cant go in a js string as is gets escaped again
+fn string_to_javascript(value: &str) -> String {
+    let mut res = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            '\r' => res.push_str("\\r"),
+            //line and paragraph separators end a line in older js engines
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => res.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn number_to_javascript(text: &str, kind: NumberKind) -> String {
+    let digits = kind.digits(text);
+    match kind {
+        NumberKind::Int { radix: 16 } => format!("0x{}", digits),
+        NumberKind::Int { radix: 8 } => format!("0o{}", digits),
+        NumberKind::Int { radix: 2 } => format!("0b{}", digits),
+        //a leading 0 makes it an old style octal number in js
+        NumberKind::Int { .. } => strip_leading_zeros(&digits).to_string(),
+        NumberKind::Float => digits,
+    }
+}
+
+fn strip_leading_zeros(digits: &str) -> &str {
+    let stripped = digits.trim_start_matches('0');
+    if stripped.is_empty() { "0" } else { stripped }
+}
+
+impl FunctionTokens<'_> {
     pub fn function_header_generate_javascript_code(&self, depth: usize, is_class_method: bool) -> String {
         let indent = "    ".repeat(depth);
         let param_list = self.params.iter()
@@ -31,52 +200,91 @@ impl FunctionTokens {
 
     fn js_generate_body_from_tokens(&self, tokens: &[ValidInCodeBlock], depth: usize) -> String {
         let mut output = String::new();
+        let indent = "    ".repeat(depth);
         let mut i = 0;
         while i < tokens.len() {
             match &tokens[i] {
                 ValidInCodeBlock::WhileStartMarker => {
                     // Next should be condition, then body, then JumpIndex
                     if let Some(ValidInCodeBlock::Expression(cond)) = tokens.get(i + 1) {
-                        output.push_str(&format!("{}while ({}) {{\n", "    ".repeat(depth), self.expression_to_javascript(cond)));
-                        // Find the body (from i+2 to JumpIndex)
-                        let mut j = i + 2;
-                        let mut body_tokens = Vec::new();
-                        while j < tokens.len() {
-                            if let ValidInCodeBlock::JumpIndex(_) = tokens[j] {
-                                break;
-                            }
-                            body_tokens.push(tokens[j].clone());
-                            j += 1;
-                        }
-                        output.push_str(&self.js_generate_body_from_tokens(&body_tokens, depth + 1));
-                        output.push_str(&format!("{}}}\n", "    ".repeat(depth)));
-                        i = j + 1; // Skip past JumpIndex
+                        output.push_str(&format!("{}while ({}) {{\n", indent, expression_to_javascript(cond)));
+                        let end = block_end(tokens, i);
+                        output.push_str(&self.js_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                        output.push_str(&format!("{}}}\n", indent));
+                        i = end + 1; // Skip past JumpIndex
                         continue;
                     }
                 }
+                ValidInCodeBlock::ForStartMarker(head) => {
+                    output.push_str(&format!("{}{} {{\n", indent, for_head_to_javascript(head)));
+                    let end = block_end(tokens, i);
+                    output.push_str(&self.js_generate_body_from_tokens(&tokens[i + 1..end], depth + 1));
+                    output.push_str(&format!("{}}}\n", indent));
+                    i = end + 1;
+                    continue;
+                }
                 ValidInCodeBlock::IfStartMarker => {
                     // Next should be condition, then body, then JumpIndex
                     if let Some(ValidInCodeBlock::Expression(cond)) = tokens.get(i + 1) {
-                        let condition_str = self.expression_to_javascript(cond);
+                        let condition_str = expression_to_javascript(cond);
                         // Check if the condition is just a variable named "if" (parser error)
                         let condition = if condition_str == "if" { "true" } else { &condition_str };
-                        output.push_str(&format!("{}if ({}) {{\n", "    ".repeat(depth), condition));
-                        // Find the body (from i+2 to JumpIndex)
-                        let mut j = i + 2;
-                        let mut body_tokens = Vec::new();
-                        while j < tokens.len() {
-                            if let ValidInCodeBlock::JumpIndex(_) = tokens[j] {
-                                break;
+                        output.push_str(&format!("{}if ({}) {{\n", indent, condition));
+                        let end = block_end(tokens, i);
+                        output.push_str(&self.js_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                        i = end + 1; // Skip past JumpIndex
+                        // the else ifs and the else of this if follow right after it
+                        loop {
+                            match (tokens.get(i), tokens.get(i + 1)) {
+                                (Some(ValidInCodeBlock::ElseIfStartMarker), Some(ValidInCodeBlock::Expression(cond))) => {
+                                    output.push_str(&format!("{}}} else if ({}) {{\n", indent, expression_to_javascript(cond)));
+                                    let end = block_end(tokens, i);
+                                    output.push_str(&self.js_generate_body_from_tokens(&tokens[i + 2..end], depth + 1));
+                                    i = end + 1;
+                                }
+                                (Some(ValidInCodeBlock::ElseStartMarker), _) => {
+                                    output.push_str(&format!("{}}} else {{\n", indent));
+                                    let end = block_end(tokens, i);
+                                    output.push_str(&self.js_generate_body_from_tokens(&tokens[i + 1..end], depth + 1));
+                                    i = end + 1;
+                                    break;
+                                }
+                                _ => break,
                             }
-                            body_tokens.push(tokens[j].clone());
-                            j += 1;
                         }
-                        output.push_str(&self.js_generate_body_from_tokens(&body_tokens, depth + 1));
-                        output.push_str(&format!("{}}}\n", "    ".repeat(depth)));
-                        i = j + 1; // Skip past JumpIndex
+                        output.push_str(&format!("{}}}\n", indent));
                         continue;
                     }
                 }
+                //the subject is only worked out once, the arms check its tag in order and _ is the else
+                ValidInCodeBlock::MatchStartMarker(subject) => {
+                    let arm_indent = "    ".repeat(depth + 1);
+                    output.push_str(&format!("{}{{\n{}const _match = {};\n", indent, arm_indent, expression_to_javascript(subject)));
+                    let arms = match_arms(tokens, i);
+                    for (index, (pattern, body)) in arms.iter().enumerate() {
+                        let opener = match (index, pattern) {
+                            (0, Some(pattern)) => format!("if (_match.tag === {}) {{", string_to_javascript(&pattern.variant)),
+                            (_, Some(pattern)) => format!("}} else if (_match.tag === {}) {{", string_to_javascript(&pattern.variant)),
+                            (0, None) => "{".to_string(),
+                            (_, None) => "} else {".to_string(),
+                        };
+                        output.push_str(&format!("{}{}\n", arm_indent, opener));
+                        if let Some(pattern) = pattern {
+                            output.push_str(&bindings_to_javascript(pattern, &"    ".repeat(depth + 2)));
+                        }
+                        output.push_str(&self.js_generate_body_from_tokens(&tokens[body.clone()], depth + 2));
+                        //arms after _ can never run
+                        if pattern.is_none() {
+                            break;
+                        }
+                    }
+                    if !arms.is_empty() {
+                        output.push_str(&format!("{}}}\n", arm_indent));
+                    }
+                    output.push_str(&format!("{}}}\n", indent));
+                    i = block_end(tokens, i) + 1;
+                    continue;
+                }
                 ValidInCodeBlock::JumpIndex(_) => {
                     // Should be handled by the above logic, just skip
                     i += 1;
@@ -95,7 +303,7 @@ impl FunctionTokens {
         let indent = "    ".repeat(depth);
         match statement {
             ValidInCodeBlock::Expression(expr) => {
-                format!("{}{};\n", indent, self.expression_to_javascript(expr))
+                format!("{}{};\n", indent, expression_to_javascript(expr))
             }
             ValidInCodeBlock::Var(var) => {
                 format!("{}{};\n", indent, var.generate_javascript_code(depth))
@@ -109,41 +317,40 @@ impl FunctionTokens {
             ValidInCodeBlock::IfStartMarker => {
                 format!("{}if ", indent)
             }
+            ValidInCodeBlock::ElseIfStartMarker => {
+                format!("{}else if ", indent)
+            }
+            ValidInCodeBlock::ElseStartMarker => {
+                format!("{}else ", indent)
+            }
+            ValidInCodeBlock::ForStartMarker(head) => {
+                format!("{}{} ", indent, for_head_to_javascript(head))
+            }
+            ValidInCodeBlock::Return(Some(value), _) => {
+                format!("{}return {};\n", indent, expression_to_javascript(value))
+            }
+            ValidInCodeBlock::Return(None, _) => {
+                format!("{}return;\n", indent)
+            }
+            ValidInCodeBlock::Break(_) => {
+                format!("{}break;\n", indent)
+            }
+            ValidInCodeBlock::Continue(_) => {
+                format!("{}continue;\n", indent)
+            }
             ValidInCodeBlock::JumpIndex(_) => {
                 String::new() // Handle jump logic if needed
             }
+            //matches are written out whole by js_generate_body_from_tokens
+            ValidInCodeBlock::MatchStartMarker(_) | ValidInCodeBlock::MatchArmStartMarker(..) => String::new(),
             ValidInCodeBlock::HeadEndAndBodyStartMarker => {
                 format!("{} {{\n", indent)
             }
         }
     }
-    
-    fn expression_to_javascript(&self, expr: &Expression) -> String {
-        // Convert Expression to JavaScript code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                // Check if this is a control flow function call that should be handled specially
-                if func_call.name == "while" || func_call.name == "if" {
-                    // For control flow, just return the condition without the function name
-                    if func_call.params.len() == 1 {
-                        self.expression_to_javascript(&func_call.params[0])
-                    } else {
-                        func_call.generate_javascript_code(0)
-                    }
-                } else {
-                    func_call.generate_javascript_code(0)
-                }
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "null".to_string(),
-        }
-    }
 }
 
-impl ParamTokens {
+impl ParamTokens<'_> {
     pub fn generate_javascript_code(&self, _depth: usize) -> String {
         match &self.default_value.0 {
             crate::parser::expression::ExpressionPiece::Placeholder(false) => {
@@ -158,67 +365,53 @@ impl ParamTokens {
     }
 }
 
-impl VarTokens {
+impl VarTokens<'_> {
     pub fn generate_javascript_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
         let type_name = self.type_.name;
-        let value_str = self.expression_to_javascript(&self.default_value);
+        let value_str = expression_to_javascript(&self.default_value);
         if type_name != "" && type_name != "None" && type_name != "_" {
             format!("{}let {} = {}; // type: {}", indent, self.name, value_str, type_name)
         } else {
             format!("{}let {} = {}", indent, self.name, value_str)
         }
     }
-    
-    fn expression_to_javascript(&self, expr: &Expression) -> String {
-        // Convert Expression to JavaScript code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_javascript_code(0)
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "null".to_string(),
-        }
-    }
 }
 
-impl FunctionCallTokens {
+impl FunctionCallTokens<'_> {
     pub fn generate_javascript_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
-        let operator_names = ["=", "+", "-", "*", "/", "%", ">", "<", ">=", "<=", "==", "!=", "&&", "||", "^", "|", "&"]; // Add more as needed
-        if operator_names.contains(&self.name) && self.params.len() == 2 {
+        if let [Expression(crate::parser::expression::ExpressionPiece::MapIndex(map, key), _), value] = self.params.as_slice()
+            && ASSIGNMENT_OPERATORS.contains(&self.name.as_str())
+        {
+            return format!("{}{}", indent, map_assignment_to_javascript(&self.name, map, key, value));
+        }
+        if precedence(&self.name).is_some() && self.params.len() == 2 {
             // Infix notation for binary operators
-            let left = self.expression_to_javascript(&self.params[0]);
-            let right = self.expression_to_javascript(&self.params[1]);
-            format!("{}{} {} {}", indent, left, self.name, right)
+            let left = parenthesize(expression_to_javascript(&self.params[0]), needs_parentheses(&self.name, &self.params[0], false));
+            let right = parenthesize(expression_to_javascript(&self.params[1]), needs_parentheses(&self.name, &self.params[1], true));
+            //== in javascript converts between types first, the strict versions compare like the language does
+            let operator = match self.name.as_str() {
+                "==" => "===",
+                "!=" => "!==",
+                operator => operator,
+            };
+            format!("{}{} {} {}", indent, left, operator, right)
+        } else if PREFIX_OPERATORS.contains(&self.name.as_str()) && self.params.len() == 1 {
+            //- -a has to stay apart or it turns into a decrement
+            let operand = parenthesize(expression_to_javascript(&self.params[0]), is_operator_call(&self.params[0]));
+            format!("{}{}{}", indent, self.name, operand)
         } else {
             let params = self.params.iter()
-                .map(|param| self.expression_to_javascript(param))
+                .map(|param| expression_to_javascript(param))
                 .collect::<Vec<String>>()
                 .join(", ");
             format!("{}{}({})", indent, self.name, params)
         }
     }
-    
-    fn expression_to_javascript(&self, expr: &Expression) -> String {
-        // Convert Expression to JavaScript code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_javascript_code(0)
-            }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "null".to_string(),
-        }
-    }
 }
 
-impl FileTokens {
+impl FileTokens<'_> {
     pub fn generate_javascript_code(&self, depth: usize) -> String {
         let mut output = String::new();
         
@@ -246,26 +439,28 @@ impl FileTokens {
     }
 }
 
-impl ClassTokens {
+impl ClassTokens<'_> {
     pub fn generate_javascript_code(&self, depth: usize) -> String {
         let indent = "    ".repeat(depth);
         let mut output = format!("{}class {} {{\n", indent, self.name);
         
-        // Add constructor method
+        // Add constructor method, it takes every field in order so a construction can pass them all.
+        // the defaults keep `new Name()` working the way it did before there were constructions
         let constructor_indent = "    ".repeat(depth + 1);
-        output.push_str(&format!("{}constructor() {{\n", constructor_indent));
-        
+        let params = self
+            .fields
+            .iter()
+            .map(|field| match &field.default_value.0 {
+                crate::parser::expression::ExpressionPiece::Placeholder(false) => format!("{} = null", field.name),
+                _ => format!("{} = {}", field.name, expression_to_javascript(&field.default_value)),
+            })
+            .collect::<Vec<String>>();
+        output.push_str(&format!("{}constructor({}) {{\n", constructor_indent, params.join(", ")));
+
         // Generate class fields in constructor
         for field in &self.fields {
             let field_indent = "    ".repeat(depth + 2);
-            match &field.default_value.0 {
-                crate::parser::expression::ExpressionPiece::Placeholder(false) => {
-                    output.push_str(&format!("{}this.{} = null;\n", field_indent, field.name));
-                }
-                _ => {
-                    output.push_str(&format!("{}this.{} = {};\n", field_indent, field.name, self.expression_to_javascript(&field.default_value)));
-                }
-            }
+            output.push_str(&format!("{}this.{} = {};\n", field_indent, field.name, field.name));
         }
         
         output.push_str(&format!("{}}}\n", constructor_indent));
@@ -282,18 +477,77 @@ impl ClassTokens {
         
         output
     }
-    
-    fn expression_to_javascript(&self, expr: &Expression) -> String {
-        // Convert Expression to JavaScript code
-        match &expr.0 {
-            crate::parser::expression::ExpressionPiece::Variable(name) => name.to_string(),
-            crate::parser::expression::ExpressionPiece::StringLiteral(value) => format!("\"{}\"", value),
-            crate::parser::expression::ExpressionPiece::NumberLiteral(value) => value.to_string(),
-            crate::parser::expression::ExpressionPiece::Operator(op) => op.to_string(),
-            crate::parser::expression::ExpressionPiece::FunctionCall(func_call) => {
-                func_call.generate_javascript_code(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::file::File;
+    use crate::project_basic_utils::tokenizer::Tokenizer;
+    use std::process::Command;
+
+    /// compiles the code to javascript, runs it with the driver after it and gives back what it printed
+    fn run_javascript(code: &str, driver: &str) -> String {
+        let mut file = File::new(Tokenizer::new(file!(), line!() as usize, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        assert!(!file.tokenizer.diagnostics.has_errors(), "{}", file.tokenizer.render_diagnostics());
+        let javascript = format!("{}\n{}", file.generate_javascript_code(0), driver);
+        let output = Command::new("node")
+            .arg("-e")
+            .arg(&javascript)
+            .output()
+            .expect("node has to be installed to run the javascript tests");
+        assert!(output.status.success(), "{}\n{}", javascript, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_methods_reach_fields_and_methods_through_this() {
+        let code = "class Engine {
+                int power = 3
+                function vroom() int {
+                    return power * 2
+                }
             }
-            crate::parser::expression::ExpressionPiece::Placeholder(_) => "null".to_string(),
-        }
+
+            class Car {
+                int id = 4
+                Engine engine
+                function next(int step) int {
+                    return id + step
+                }
+                function both() int {
+                    return next(10) + engine.vroom()
+                }
+                function shadowed(int id) int {
+                    return id
+                }
+            }
+            ";
+        let driver = "const car = new Car(1, new Engine());\nconsole.log(car.next(2), car.both(), car.shadowed(7));";
+        assert_eq!(run_javascript(code, driver), "3 17 7\n");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_maps_keep_their_keys() {
+        let code = "function weighted() int {
+                let map<int, int> squares = [1: 1, 2: 4]
+                squares[3] = 9
+                squares[2] += 1
+                let int total = 0
+                for key, value in squares {
+                    total += key * value
+                }
+                return total + squares[3]
+            }
+
+            function ages() int {
+                let map<string, int> ages = [\"bob\": 3]
+                ages[\"al\"] = 4
+                return ages[\"bob\"] * 10 + ages[\"al\"]
+            }
+            ";
+        let driver = "console.log(weighted(), ages());";
+        assert_eq!(run_javascript(code, driver), "47 34\n");
+    }
+}
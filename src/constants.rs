@@ -1,60 +1,14 @@
 use crate::libs::mapTrie::MapTrie;
 use crate::libs::trie::Trie;
+use crate::project_basic_utils::keyword::Keyword;
 use std::sync::LazyLock;
 
 pub static SPACE_CHARS: LazyLock<Vec<char>> = LazyLock::new(|| vec![' ', '\t', '\n', '\r']);
 
-pub static OPERATORS: LazyLock<Vec<char>> = LazyLock::new(|| {
-    vec![
-        '+', '-', '*', '/', '%', '=', '!', '&', '|', '<', '>', '^', '~',
-    ]
-});
-
 pub static KEYWORDS_TRIE: LazyLock<Trie> = LazyLock::new(|| {
     let mut trie = Trie::new();
-    let keywords = vec![
-        "fn",
-        "let",
-        "mut",
-        "if",
-        "else",
-        "while",
-        "for",
-        "in",
-        "return",
-        "break",
-        "continue",
-        "struct",
-        "enum",
-        "impl",
-        "use",
-        "mod",
-        "pub",
-        "const",
-        "static",
-        "match",
-        "as",
-        "trait",
-        "type",
-        "where",
-        "self",
-        "super",
-        "crate",
-        "extern",
-        "ref",
-        "move",
-        "unsafe",
-        "dyn",
-        "async",
-        "await",
-        "box",
-        "macro_rules",
-        "class",
-        "function",
-    ];
-
-    for keyword in keywords {
-        trie.insert(&keyword.to_string());
+    for keyword in Keyword::ALL {
+        trie.insert(&keyword.as_str().to_string());
     }
     trie
 });
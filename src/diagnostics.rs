@@ -0,0 +1,277 @@
+use serde::Serialize;
+
+use crate::line_index::LineIndex;
+use crate::utils::{blue, red, yellow};
+
+type Int = usize;
+
+/// every diagnostic carries one of these so tools can match on the kind of error without parsing the message
+pub mod codes {
+    pub const SYNTAX_ERROR: &str = "E0001";
+    pub const UNEXPECTED_END_OF_FILE: &str = "E0002";
+    pub const NOT_ALLOWED_AT_TOP_LEVEL: &str = "E0003";
+    pub const UNTERMINATED_STRING: &str = "E0004";
+    pub const RESERVED_WORD: &str = "E0005";
+    pub const UNKNOWN_TYPE: &str = "E0100";
+    pub const UNKNOWN_NAME: &str = "E0101";
+    pub const UNKNOWN_FUNCTION: &str = "E0102";
+    pub const MISMATCHED_TYPES: &str = "E0103";
+    pub const NOT_ITERABLE: &str = "E0104";
+    pub const OUTSIDE_OF_LOOP: &str = "E0105";
+    pub const UNKNOWN_MEMBER: &str = "E0106";
+    pub const NOT_INDEXABLE: &str = "E0107";
+    pub const MISSING_FIELD: &str = "E0108";
+    pub const DUPLICATE_FIELD: &str = "E0109";
+    pub const NON_EXHAUSTIVE_MATCH: &str = "E0110";
+    pub const WRONG_PAYLOAD_COUNT: &str = "E0111";
+    pub const DUPLICATE_DEFINITION: &str = "E0112";
+    pub const MISSING_RETURN: &str = "E0113";
+    pub const RANGE_OUTSIDE_FOR: &str = "E0114";
+    pub const UNREACHABLE_ARM: &str = "W0001";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// byte offsets into the source code of a file, end is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Ord, PartialOrd)]
+pub struct Span {
+    pub start: Int,
+    pub end: Int,
+}
+
+impl Span {
+    pub fn new(start: Int, end: Int) -> Self {
+        Self { start, end }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub span: Span,
+    pub message: String,
+    pub notes: Vec<String>,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            span,
+            message: message.into(),
+            notes: vec![],
+            suggestion: None,
+        }
+    }
+
+    pub fn warning(code: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(code, span, message)
+        }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// returned by the parsers once the error has been reported to the sink,
+/// it carries nothing bc the diagnostic already holds everything the user needs to see
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError;
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// every file collects its own diagnostics so one run can report all of them instead of stopping at the first
+#[derive(Debug, Default)]
+pub struct DiagnosticSink {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DiagnosticSink {
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    pub fn render(&self, file_name: &str, code: &str, lines: &LineIndex) -> String {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| render_diagnostic(diagnostic, file_name, code, lines))
+            .collect()
+    }
+
+    /// one json object per line, for editors and ci annotators
+    pub fn render_json(&self, file_name: &str, code: &str, lines: &LineIndex) -> String {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let json = JsonDiagnostic::new(diagnostic, file_name, code, lines);
+                serde_json::to_string(&json).expect("a diagnostic is always serializable") + "\n"
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonDiagnostic<'a> {
+    pub file: &'a str,
+    pub line: Int,
+    pub column: Int,
+    pub end_line: Int,
+    pub end_column: Int,
+    pub severity: Severity,
+    pub code: &'a str,
+    pub message: &'a str,
+    pub notes: &'a [String],
+    pub suggestion: Option<&'a str>,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    pub fn new(diagnostic: &'a Diagnostic, file_name: &'a str, code: &str, lines: &LineIndex) -> Self {
+        let (line, column) = lines.line_and_column(code, diagnostic.span.start);
+        let (end_line, end_column) = lines.line_and_column(code, diagnostic.span.end);
+        Self {
+            file: file_name,
+            line,
+            column,
+            end_line,
+            end_column,
+            severity: diagnostic.severity,
+            code: diagnostic.code,
+            message: &diagnostic.message,
+            notes: &diagnostic.notes,
+            suggestion: diagnostic.suggestion.as_deref(),
+        }
+    }
+}
+
+pub fn render_diagnostic(diagnostic: &Diagnostic, file_name: &str, code: &str, lines: &LineIndex) -> String {
+    let (line, column) = lines.line_and_column(code, diagnostic.span.start);
+    let Span { start: line_start, end: line_end } = lines.line_span(code, diagnostic.span.start);
+    let source_line = &code[line_start..line_end];
+    //spans that cross lines only get underlined up to the end of the first line
+    let underline_end = diagnostic.span.end.clamp(diagnostic.span.start, line_end);
+    let underline_len = code[diagnostic.span.start..underline_end].chars().count().max(1);
+
+    let gutter = " ".repeat(line.to_string().len());
+    let severity = match diagnostic.severity {
+        Severity::Error => red("error".to_string()),
+        Severity::Warning => yellow("warning".to_string()),
+    };
+    let mut output = format!(
+        "{}[{}]: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}\n",
+        severity,
+        diagnostic.code,
+        diagnostic.message,
+        gutter,
+        blue(&format!("{}:{}:{}", file_name, line, column)),
+        gutter,
+        line,
+        source_line,
+        gutter,
+        " ".repeat(column - 1),
+        red("^".repeat(underline_len))
+    );
+    for note in &diagnostic.notes {
+        output.push_str(&format!("{} = note: {}\n", gutter, note));
+    }
+    if let Some(suggestion) = &diagnostic.suggestion {
+        output.push_str(&format!("{} = help: {}\n", gutter, suggestion));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sink_counts_only_errors() {
+        let mut sink = DiagnosticSink::default();
+        sink.push(Diagnostic::warning(codes::SYNTAX_ERROR, Span::new(0, 1), "just a warning"));
+        assert!(!sink.has_errors());
+        sink.push(Diagnostic::error(codes::SYNTAX_ERROR, Span::new(0, 1), "an error"));
+        sink.push(Diagnostic::error(codes::SYNTAX_ERROR, Span::new(2, 3), "another error"));
+        assert_eq!(sink.error_count(), 2);
+    }
+
+    #[test]
+    fn test_render_points_at_the_span() {
+        let code = "let int a = 9\nlet Peson b = 2\n";
+        let diagnostic = Diagnostic::error(codes::UNKNOWN_TYPE, Span::new(18, 23), "unknown type Peson")
+            .with_note("types have to be declared")
+            .with_suggestion("did you mean Person");
+        let rendered = render_diagnostic(&diagnostic, "example.bob", code, &LineIndex::new(code, 1));
+        assert!(rendered.contains("example.bob:2:5"));
+        assert!(rendered.contains("2 | let Peson b = 2"));
+        assert!(rendered.contains("^^^^^"));
+        assert!(rendered.contains("= note: types have to be declared"));
+        assert!(rendered.contains("= help: did you mean Person"));
+    }
+
+    #[test]
+    fn test_json_has_one_object_per_line() {
+        let code = "let int a = 9\nlet Peson b = 2\n";
+        let mut sink = DiagnosticSink::default();
+        sink.push(Diagnostic::error(codes::UNKNOWN_TYPE, Span::new(18, 23), "unknown type Peson"));
+        sink.push(Diagnostic::warning(codes::SYNTAX_ERROR, Span::new(0, 3), "a warning"));
+        let json = sink.render_json("example.bob", code, &LineIndex::new(code, 1));
+        let lines: Vec<&str> = json.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["file"], "example.bob");
+        assert_eq!(first["line"], 2);
+        assert_eq!(first["column"], 5);
+        assert_eq!(first["end_line"], 2);
+        assert_eq!(first["end_column"], 10);
+        assert_eq!(first["severity"], "error");
+        assert_eq!(first["code"], codes::UNKNOWN_TYPE);
+        assert_eq!(first["message"], "unknown type Peson");
+
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["severity"], "warning");
+    }
+
+    #[test]
+    fn test_columns_are_counted_in_chars() {
+        let code = "let string s = 'größe' + nme\n";
+        let start = code.find("nme").unwrap();
+        let lines = LineIndex::new(code, 1);
+        assert_eq!(lines.line_and_column(code, start), (1, 26));
+        let rendered = render_diagnostic(
+            &Diagnostic::error(codes::UNKNOWN_NAME, Span::new(start, start + 3), "cannot find `nme`"),
+            "example.bob",
+            code,
+            &lines,
+        );
+        assert!(rendered.contains("example.bob:1:26"));
+        assert!(rendered.contains(&format!("{}{}", " ".repeat(25), red("^^^".to_string()))));
+    }
+}
@@ -1,13 +1,16 @@
-use std::{fmt::format, vec};
+use std::{fmt::format, fs, path::Path, vec};
 
 use crate::{
+    cli::EmitTarget,
+    diagnostics::{codes, Diagnostic, ParseResult, Span},
     parser::{
         class_parser::Class,
         code_block::CodeBlock,
+        enum_parser::Enum,
         function_parser::Function,
         type_parser::Type_,
         var_parser::Var,
-    }, project_basic_utils::{token::TokenType, tokenizer::Tokenizer}, utils::green
+    }, project_basic_utils::{keyword::Keyword, token::TokenType, tokenizer::Tokenizer}
 };
 
 
@@ -20,21 +23,23 @@ pub enum CompilationStage {
     Done = 4,
 }
 
-pub struct File {
-    pub functions: Vec<Function>,
-    pub classes: Vec<Class>,
-    pub variables: Vec<Var>,
-    pub builtins: Vec<Type_>,
-    pub tokenizer: Tokenizer,
+pub struct File<'src> {
+    pub functions: Vec<Function<'src>>,
+    pub classes: Vec<Class<'src>>,
+    pub enums: Vec<Enum<'src>>,
+    pub variables: Vec<Var<'src>>,
+    pub builtins: Vec<Type_<'src>>,
+    pub tokenizer: Tokenizer<'src>,
     pub stage: CompilationStage,
 }
 
-impl File {
-    pub fn new(t: Tokenizer) -> Self {
+impl<'src> File<'src> {
+    pub fn new(t: Tokenizer<'src>) -> Self {
         Self {
             tokenizer: t,
             functions: Vec::new(),
             classes: Vec::new(),
+            enums: Vec::new(),
             variables: Vec::new(),
             stage: CompilationStage::Start,
             builtins: vec![
@@ -43,6 +48,7 @@ impl File {
                     sub_types: Vec::new(),
                     is_optional: false,
                     is_pointer: false,
+                    span: Span::default(),
                 },
                 Type_ {
                     name: "Container", //just making sure that non built in generics work
@@ -51,15 +57,39 @@ impl File {
                         sub_types: Vec::new(),
                         is_optional: false,
                         is_pointer: false,
+                        span: Span::default(),
                     }],
                     is_optional: false,
                     is_pointer: false,
+                    span: Span::default(),
                 },
                 Type_ {
                     name: "string",
                     sub_types: Vec::new(),
                     is_optional: false,
                     is_pointer: false,
+                    span: Span::default(),
+                },
+                Type_ {
+                    name: "float",
+                    sub_types: Vec::new(),
+                    is_optional: false,
+                    is_pointer: false,
+                    span: Span::default(),
+                },
+                Type_ {
+                    name: "bool",
+                    sub_types: Vec::new(),
+                    is_optional: false,
+                    is_pointer: false,
+                    span: Span::default(),
+                },
+                Type_ {
+                    name: "char",
+                    sub_types: Vec::new(),
+                    is_optional: false,
+                    is_pointer: false,
+                    span: Span::default(),
                 },
                 Type_ {
                     name: "array",
@@ -68,9 +98,11 @@ impl File {
                         sub_types: Vec::new(),
                         is_optional: false,
                         is_pointer: false,
+                        span: Span::default(),
                     }],
                     is_optional: false,
                     is_pointer: false,
+                    span: Span::default(),
                 },
                 Type_ {
                     name: "array",
@@ -79,148 +111,139 @@ impl File {
                         sub_types: Vec::new(),
                         is_optional: false,
                         is_pointer: false,
+                        span: Span::default(),
                     }],
                     is_optional: false,
                     is_pointer: false,
+                    span: Span::default(),
                 },
             ],
         }
     }
 
-    fn type_check_vars(&self) {
-        for var in &self.variables {
-            if !self.is_allowed_type(&var.type_) {
-                panic!(
-                    "Variable {} of type {} is not allowed",
-                    var.name,
-                    var.type_.to_string()
-                );
-            }
-        }
-    }
+    pub fn generate_syntax_tree_from_source_code(&mut self) {
 
-    fn type_check_classes(&self) {
-        for _class in &self.classes {
-            for field in &_class.fields {
-                if !self.is_allowed_type(&field.type_) {
-                    panic!(
-                        "type {} (used as field {} of class {}) is unknown to the compiler",
-                        field.type_.to_string(),
-                        field.name,
-                        green(&_class.name.to_string())
-                    );
-                }
+        while self.tokenizer.in_range() {
+            let item_start = self.tokenizer.peek(0).span.start;
+            if self.parse_top_level_item().is_err() {
+                //panic mode recovery: the error is already in the diagnostics, so skip to the next thing that looks like a top level item and keep going
+                let max_indentation = self.tokenizer.indentation_at(item_start);
+                self.tokenizer.skip_to_next_line_starting_with(&Keyword::TOP_LEVEL, max_indentation);
             }
         }
+
+        
     }
 
-    fn type_check_functions(&self) {
-        for function in &self.functions {
-            for param in &function.params {
-                if !self.is_allowed_type(&param.type_) {
-                    panic!(
-                        "type {} (used as param {} of function {}) is unknown to the compiler",
-                        param.name,
-                        param.type_.to_string(),
-                        function.name
-                    );
-                }
+    fn parse_top_level_item(&mut self) -> ParseResult<()> {
+        let keyword_start = self.tokenizer.peek(0).span.start;
+        let word = self.tokenizer.expect(TokenType::KEYWORD)?;
+        match Keyword::from_word(word).expect("the tokenizer only gives back words from the keyword table") {
+            Keyword::Class => {
+                let _class = Class::new(&mut self.tokenizer)?;
+                // (&_class).display(); //for debug like info
+                self.classes.push(_class);
             }
-            if function.return_type.name != "void" && !self.is_allowed_type(&function.return_type) {
-                panic!(
-                    "type {} (used as return type of function {}) is unknown for the compiler",
-                    function.return_type.name, function.name
-                );
+            Keyword::Enum => {
+                let _enum = Enum::new(&mut self.tokenizer)?;
+                self.enums.push(_enum);
+            }
+            Keyword::Function => {
+                let _function = Function::new(&mut self.tokenizer)?;
+                // (&_function).display(); //for debug like info
+                self.functions.push(_function);
+            }
+            Keyword::Const | Keyword::Let => {
+                let _var = Var::new(&mut self.tokenizer)?;
+                // (&_var).display(); //for debug like info
+                self.variables.push(_var);
+            }
+            _ => {
+                return Err(self.tokenizer.report(
+                    Diagnostic::error(
+                        codes::NOT_ALLOWED_AT_TOP_LEVEL,
+                        self.tokenizer.span_from(keyword_start),
+                        format!("`{}` is not allowed at the top level of a file", word),
+                    )
+                    .with_note("only class, enum, function, let and const can appear outside of a function"),
+                ));
             }
-
         }
+        Ok(())
     }
 
-    pub fn type_check(&self) {
-        self.type_check_vars();
-        self.type_check_classes();
-        self.type_check_functions();
+    pub fn get_base_file_name(&self) -> String {
+        std::path::Path::new(self.tokenizer.file_name)
+            .file_stem()
+            .expect("Failed to get file name")
+            .to_string_lossy()
+            .to_string()
     }
 
-    fn is_allowed_type(&self, type_: &Type_) -> bool {
-        for class in &self.classes {
-            if class.name == type_.name {
-                return true;
-            }
+    pub fn output_code_from_syntax_tree(&self, out_dir: &Path, targets: &[EmitTarget]) -> std::io::Result<()> {
+        if targets.is_empty() {
+            return Ok(());
         }
-        if self.builtins.contains(type_) {
-            return true;
-        }
-        return false;
-    }
-
-
-
-
-
+        let file_base_name = self.get_base_file_name();
 
-    pub fn generate_syntax_tree_from_source_code(&mut self) {
+        // Create output directory if it doesn't exist
+        fs::create_dir_all(out_dir)?;
 
-        while self.tokenizer.in_range() {
-            match self.tokenizer.expect(TokenType::KEYWORD) {
-                "class" => {
-                    let _class = Class::new(&mut self.tokenizer);
-                    // (&_class).display(); //for debug like info
-                    self.classes.push(_class);
-                }
-                "function" => {
-                    let _function = Function::new(&mut self.tokenizer);
-                    // (&_function).display(); //for debug like info
-                    self.functions.push(_function);
+        for target in targets {
+            match target {
+                EmitTarget::Javascript => {
+                    let js_code = self.generate_javascript_code(0);
+                    fs::write(out_dir.join(format!("{}.js", file_base_name)), js_code)?;
                 }
-                "const" => {
-                    let _var = Var::new(&mut self.tokenizer);
-                    // (&_var).display(); //for debug like info
-                    self.variables.push(_var);
-                }
-                "let" => {
-                    let _var = Var::new(&mut self.tokenizer);
-                    // (&_var).display(); //for debug like info
-                    self.variables.push(_var);
-                }
-                token_string => {
-                    self.tokenizer.expect_char('\n');
-                    println!("unknown token: {}", token_string);
+                EmitTarget::Cpp => {
+                    let cpp_header_file = self.generate_cpp_header_file();
+                    let cpp_code = self.generate_cpp_code(0);
+                    fs::write(out_dir.join(format!("{}.hpp", file_base_name)), cpp_header_file)?;
+                    fs::write(out_dir.join(format!("{}.cpp", file_base_name)), cpp_code)?;
                 }
             }
-            self.tokenizer.eat_all_spaces();
         }
-
-        
+        Ok(())
     }
+}
 
-    pub fn get_base_file_name(&self) -> String {
-        self.tokenizer.file_name.split('.').collect::<Vec<_>>()[0].to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsing_goes_on_after_a_broken_top_level_item() {
+        //recovery skips to a line thats indented no deeper than the broken item, so the items all start at the left edge
+        let code = "function broken(int) int {\n    return 1\n}\nlet int = 5\nfunction works() int {\n    return 2\n}\n";
+        let mut file = File::new(Tokenizer::new("recovery.bob", 1, code));
+        file.generate_syntax_tree_from_source_code();
+        let codes: Vec<&str> = file.tokenizer.diagnostics.diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+        assert_eq!(codes, vec![codes::SYNTAX_ERROR; 2], "{}", file.tokenizer.render_diagnostics());
+        let lines: Vec<usize> = file
+            .tokenizer
+            .diagnostics
+            .diagnostics
+            .iter()
+            .map(|diagnostic| file.tokenizer.find_line_and_column(diagnostic.span.start).0)
+            .collect();
+        assert_eq!(lines, vec![1, 4]);
+        let names: Vec<&str> = file.functions.iter().map(|function| function.name.as_str()).collect();
+        assert_eq!(names, vec!["works"]);
     }
 
-    pub fn output_code_from_syntax_tree(&self) {
-        
-        let file_base_name = self.get_base_file_name();
-
-
-        let js_code = self.generate_javascript_code(0);
-        let cpp_header_file = self.generate_cpp_header_file();
-        let cpp_code = self.generate_cpp_code(0);
-    
-        
-    
-        use std::fs::File as StdFile;
-        use std::fs;
-        use std::io::Write;
-    
-        // Create output directory if it doesn't exist
-        fs::create_dir_all("output").expect("Unable to create output directory");
-    
-        let mut javascript_output_file = StdFile::create(format!("output/{}.js", file_base_name)).expect("Unable to create file");
-        let mut cpp_header_output_file = StdFile::create(format!("output/{}.hpp", file_base_name)).expect("Unable to create file");
-        let mut cpp_code_output_file = StdFile::create(format!("output/{}.cpp", file_base_name)).expect("Unable to create file");
-        javascript_output_file.write_all(js_code.as_bytes()).expect("Unable to write data");
-        cpp_header_output_file.write_all(cpp_header_file.as_bytes()).expect("Unable to write data");
-        cpp_code_output_file.write_all(cpp_code.as_bytes()).expect("Unable to write data");
+    #[test]
+    fn test_the_example_builds() {
+        //the README tells people to start with `build input`, so the example has to keep compiling as the language changes
+        let code = include_str!("../input/example.bob");
+        let mut file = File::new(Tokenizer::new("input/example.bob", 1, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        assert!(file.tokenizer.diagnostics.diagnostics.is_empty(), "{}", file.tokenizer.render_diagnostics());
+        let out_dir = std::env::temp_dir().join(format!("bob_example_{}", std::process::id()));
+        file.output_code_from_syntax_tree(&out_dir, &EmitTarget::ALL).unwrap();
+        for extension in ["js", "hpp", "cpp"] {
+            assert!(out_dir.join(format!("example.{}", extension)).is_file());
+        }
+        fs::remove_dir_all(&out_dir).unwrap();
     }
 }
@@ -0,0 +1,55 @@
+/// levenshtein distance counted in chars (insertions, deletions and substitutions all cost 1)
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    //only the previous row is needed to build the next one
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution_cost = if a_char == *b_char { 0 } else { 1 };
+            current_row.push(
+                (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1),
+            );
+        }
+        previous_row = current_row;
+    }
+    previous_row[b_chars.len()]
+}
+
+/// the candidate closest to name, as long as its close enough that it looks like a typo and not just a different name
+pub fn closest_match<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        //ties go to the first candidate so the order the caller gives is the tie breaker
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("Person", "Person"), 0);
+        assert_eq!(edit_distance("Peson", "Person"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "int"), 3);
+        assert_eq!(edit_distance("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["int", "string", "Person", "Car"];
+        assert_eq!(closest_match("Peson", candidates), Some("Person"));
+        assert_eq!(closest_match("strng", candidates), Some("string"));
+        assert_eq!(closest_match("Engine", candidates), None);
+        assert_eq!(closest_match("Car", candidates), None);
+    }
+}
@@ -76,29 +76,21 @@ impl MapTrie {
     pub fn greety(&self, word: &str) -> String {
         return word[0..self.the_most_we_can_collect_on_word(word)].to_string();
     }
+    /// how many bytes of word the longest word in the trie covers, in bytes so it can be used to slice the source code.
+    /// 0 when no word in the trie is a prefix of it
     pub fn the_most_we_can_collect_on_word(&self, word: &str) -> usize {
         let mut current_node = self.root;
-        let chars: Vec<char> = word.chars().collect();
-
-        for (i, &ch) in chars.iter().enumerate() {
-            let next = self.storage[current_node].children.get(&ch);
-            if next.is_none() {
-                panic!("we don't have this word");
+        let mut longest = 0;
+        for (i, ch) in word.char_indices() {
+            match self.storage[current_node].children.get(&ch) {
+                Some(&next) => current_node = next,
+                None => break,
             }
-            current_node = *next.unwrap();
-
             if self.storage[current_node].is_end {
-                if let Some(&next_ch) = chars.get(i + 1) {
-                    if !self.storage[current_node].children.contains_key(&next_ch) {
-                        return i + 1; // we add one because i is what the computer speaks in terms of 0 index but we need to know how many chars it can parse as the next token
-                    }
-                } else {
-                    return i + 1; // we add one because i is what the computer speaks in terms of 0 index but we need to know how many chars it can parse as the next token
-                }
+                longest = i + ch.len_utf8();
             }
         }
-
-        panic!("Looks like you're searching with a word that's not long enough");
+        longest
     }
 }
 
@@ -115,5 +107,10 @@ mod tests {
         assert_eq!(t.greety(&"!==".to_string()), "!=");
         t.insert(&"!==".to_string());
         assert_eq!(t.greety(&"!==".to_string()), "!==");
+        assert_eq!(t.the_most_we_can_collect_on_word("?"), 0);
+        t.insert(&"...".to_string());
+        //.. isnt a word so a lone . or .. isnt collected either
+        assert_eq!(t.the_most_we_can_collect_on_word("..x"), 0);
+        assert_eq!(t.the_most_we_can_collect_on_word("...x"), 3);
     }
 }
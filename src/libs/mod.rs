@@ -1,3 +1,4 @@
+pub mod edit_distance;
 pub mod linkedList;
 pub mod macros;
 pub mod mapTrie;
@@ -1,10 +1,24 @@
+use std::collections::HashMap;
+
 type NodeIndex = usize;
 
 struct Node {
+    //ascii gets looked up straight from the array, anything else goes through the map
     children: [NodeIndex; 128],
+    non_ascii_children: HashMap<char, NodeIndex>,
     is_end: bool,
 }
 
+impl Node {
+    fn new() -> Self {
+        Node {
+            children: [0; 128],
+            non_ascii_children: HashMap::new(),
+            is_end: false,
+        }
+    }
+}
+
 pub struct Trie {
     storage: Vec<Node>,
     root: NodeIndex,
@@ -16,28 +30,39 @@ impl Trie {
             storage: vec![],
             root: 0,
         };
-        res.storage.push(Node {
-            children: [0; 128],
-            is_end: false,
-        });
+        res.storage.push(Node::new());
         res.root = res.storage.len() - 1;
         res
     }
+
+    /// 0 means there is no child for that char (0 is always the root so no node can point back to it)
+    fn child(&self, node: NodeIndex, char: char) -> NodeIndex {
+        let char_index = char as usize;
+        if char_index < 128 {
+            self.storage[node].children[char_index]
+        } else {
+            self.storage[node].non_ascii_children.get(&char).copied().unwrap_or(0)
+        }
+    }
+
+    fn set_child(&mut self, node: NodeIndex, char: char, child: NodeIndex) {
+        let char_index = char as usize;
+        if char_index < 128 {
+            self.storage[node].children[char_index] = child;
+        } else {
+            self.storage[node].non_ascii_children.insert(char, child);
+        }
+    }
+
     pub fn insert(&mut self, word: &String) {
         let mut current_node = self.root;
         for char in word.chars() {
-            let char_index = char as usize;
-            if char_index >= 128 {
-                panic!("Character '{}' is not ASCII", char);
+            if self.child(current_node, char) == 0 {
+                self.storage.push(Node::new());
+                let new_node = self.storage.len() - 1;
+                self.set_child(current_node, char, new_node);
             }
-            if self.storage[current_node].children[char_index] == 0 {
-                self.storage.push(Node {
-                    children: [0; 128],
-                    is_end: false,
-                });
-                self.storage[current_node].children[char_index] = self.storage.len() - 1;
-            }
-            current_node = self.storage[current_node].children[char_index];
+            current_node = self.child(current_node, char);
         }
         self.storage[current_node].is_end = true;
     }
@@ -45,14 +70,10 @@ impl Trie {
     pub fn is_word(&self, word: &str) -> bool {
         let mut current_node = self.root;
         for char in word.chars() {
-            let char_index = char as usize;
-            if char_index >= 128 {
+            current_node = self.child(current_node, char);
+            if current_node == 0 {
                 return false;
             }
-            if self.storage[current_node].children[char_index] == 0 {
-                return false;
-            }
-            current_node = self.storage[current_node].children[char_index];
         }
         return self.storage[current_node].is_end;
     }
@@ -60,48 +81,33 @@ impl Trie {
     pub fn contains(&self, word: &str) -> bool {
         let mut current_node = self.root;
         for char in word.chars() {
-            let char_index = char as usize;
-            if char_index >= 128 {
-                return false;
-            }
-            if self.storage[current_node].children[char_index] == 0 {
+            current_node = self.child(current_node, char);
+            if current_node == 0 {
                 return false;
             }
-            current_node = self.storage[current_node].children[char_index];
         }
         return true;
     }
 
     pub fn contains_letter(&self, letter: char) -> bool {
-        let char_index = letter as usize;
-        if char_index >= 128 {
-            return false;
-        }
-        self.storage[self.root].children[char_index] != 0
+        self.child(self.root, letter) != 0
     }
 
     pub fn greety(&self, word: &str) -> String {
         //gets the biggest word it can going down the letters path
         let mut current_node = self.root;
         let mut collected_letters = Vec::new();
-        for (index, char) in word.chars().enumerate() {
-            let char_index = char as usize;
-            if char_index >= 128 {
-                panic!("Character '{}' is not ASCII", char);
-            }
-            if self.storage[current_node].children[char_index] == 0 {
+        let mut chars = word.chars().peekable();
+        while let Some(char) = chars.next() {
+            current_node = self.child(current_node, char);
+            if current_node == 0 {
                 panic!("we dont have this word");
             }
-            current_node = self.storage[current_node].children[char_index];
             collected_letters.push(char);
             if self.storage[current_node].is_end {
-                let next_letter = word.as_bytes().get(index + 1);
-                let should_continue_and_get_longer_word = next_letter.is_some() && {
-                    let next_char = next_letter.unwrap();
-                    let next_char_index = *next_char as usize;
-                    next_char_index < 128
-                        && self.storage[current_node].children[next_char_index] != 0
-                };
+                let should_continue_and_get_longer_word = chars
+                    .peek()
+                    .is_some_and(|next_char| self.child(current_node, *next_char) != 0);
                 if !should_continue_and_get_longer_word {
                     return collected_letters.iter().collect();
                 }
@@ -110,3 +116,20 @@ impl Trie {
         panic!("looks like your searching with a word thats not long enough");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_ascii_words() {
+        let mut t = Trie::new();
+        t.insert(&"größe".to_string());
+        t.insert(&"gr".to_string());
+        assert!(t.is_word("größe"));
+        assert!(t.contains("grö"));
+        assert!(!t.is_word("grö"));
+        assert_eq!(t.greety("größer"), "größe");
+        assert!(!t.contains_letter('ö'));
+    }
+}
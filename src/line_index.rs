@@ -0,0 +1,68 @@
+use crate::diagnostics::Span;
+
+type Int = usize;
+
+/// where every line of a file starts, built once per file so turning a byte offset into a line and column
+/// is a binary search instead of a walk from the top of the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    //byte offset of the first char of every line, the first entry is always 0
+    line_starts: Vec<Int>,
+    //the line number of the first line, its not always 1 bc tests and embedded code start partway into another file
+    start_line: Int,
+}
+
+impl LineIndex {
+    pub fn new(code: &str, start_line: Int) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(code.match_indices('\n').map(|(index, _)| index + 1));
+        Self { line_starts, start_line }
+    }
+
+    /// which line (counted from 0) the byte offset is on, a \n belongs to the line it ends
+    fn line_offset(&self, index: Int) -> Int {
+        self.line_starts.partition_point(|line_start| *line_start <= index) - 1
+    }
+
+    /// index is a byte offset into the code the index was built from, the column that comes back is counted in chars so it matches what editors show
+    pub fn line_and_column(&self, code: &str, index: Int) -> (Int, Int) {
+        let line_offset = self.line_offset(index);
+        let line_start = self.line_starts[line_offset];
+        (self.start_line + line_offset, code[line_start..index].chars().count() + 1)
+    }
+
+    /// the bytes of the line index is on, without its \n
+    pub fn line_span(&self, code: &str, index: Int) -> Span {
+        let line_offset = self.line_offset(index);
+        let end = self
+            .line_starts
+            .get(line_offset + 1)
+            .map_or(code.len(), |next_line_start| next_line_start - 1);
+        Span::new(self.line_starts[line_offset], end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_and_column() {
+        let code = "ab\ncgröße x\n\nlast";
+        let lines = LineIndex::new(code, 1);
+        assert_eq!(lines.line_and_column(code, 0), (1, 1));
+        assert_eq!(lines.line_and_column(code, 2), (1, 3));
+        assert_eq!(lines.line_and_column(code, 3), (2, 1));
+        assert_eq!(lines.line_and_column(code, code.find('x').unwrap()), (2, 8));
+        assert_eq!(lines.line_and_column(code, code.find("last").unwrap()), (4, 1));
+        assert_eq!(lines.line_and_column(code, code.len()), (4, 5));
+        assert_eq!(LineIndex::new(code, 10).line_and_column(code, 3), (11, 1));
+
+        let second_line = lines.line_span(code, 5);
+        assert_eq!(&code[second_line.start..second_line.end], "cgröße x");
+        let empty_line = lines.line_span(code, code.find("\n\n").unwrap() + 1);
+        assert_eq!(&code[empty_line.start..empty_line.end], "");
+        let last_line = lines.line_span(code, code.len());
+        assert_eq!(&code[last_line.start..last_line.end], "last");
+    }
+}
@@ -1,17 +1,25 @@
+mod cli;
+mod diagnostics;
 mod file;
+mod line_index;
+mod source_db;
+mod symbol;
 
 
 mod code_gen;
 mod previewScannerUtils;
 mod utils;
 use std::{fs, os};
-use std::sync::{LazyLock, Mutex};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Mutex;
 
 mod constants;
 
 mod parser;
 mod precedence_order;
 mod project_basic_utils;
+mod type_checker;
 
 mod libs;
 
@@ -26,56 +34,120 @@ use parser::class_parser::Class;
 use parser::expression::Expression;
 
 use crate::file::{CompilationStage, File};
+use crate::source_db::SourceDb;
 use crate::parser::code_block::{self, ValidInCodeBlock};
 use crate::parser::type_parser::Type_;
+use crate::utils::red;
 
 
 
 
-static FILES_TO_COMPILE: LazyLock<Vec<Mutex<File>>> = LazyLock::new(|| {
-    let mut files = Vec::new();
-
-    let input_dir = "input";
-    let entries = fs::read_dir(input_dir).expect("Failed to read input directory");
-    for entry in entries {
-        let entry = entry.expect("Failed to read directory entry");
-        let path = entry.path();
-        let file_name  = (&path).to_str().expect("Failed to get file name").to_string();
-        let file_name_without_input_dir_name = file_name.split("/").last().expect("Failed to get file name without dir name").to_string();
-        let file_contents = fs::read_to_string(&path).expect("Failed to read file");
-        if (&path).is_file() {
-            let tokenizer = Tokenizer {
-                mutex: Mutex::new(()),
-                file_name: Box::leak(file_name_without_input_dir_name.into_boxed_str()),
-                start_line: 1,
-                code: Box::leak(file_contents.into_boxed_str()),
-                parse_index: 0,
-            };
-            files.push(Mutex::new(File::new(tokenizer)));
-        }
+fn load_sources(paths: &[PathBuf]) -> std::io::Result<SourceDb> {
+    let mut db = SourceDb::default();
+    for path in paths {
+        db.load(path)?;
     }
-    files
-});
+    Ok(db)
+}
 
-fn main() {
+fn main() -> ExitCode {
     color_backtrace::install();
 
-    let mut running_threads = vec![];
-    
-    for FILE in FILES_TO_COMPILE.iter() {
-        running_threads.push(std::thread::spawn(move || {
-            let mut file = FILE.lock().unwrap();
-            //having different stages is so that when files can import from each other, they will be able to check to make sure that the file is done parsing before trying to use it
-            file.stage = CompilationStage::Parsing;
-            file.generate_syntax_tree_from_source_code();
-            file.stage = CompilationStage::TypeChecking;
-            file.type_check();
-            file.stage = CompilationStage::CodeGeneration;
-            file.output_code_from_syntax_tree();
-            file.stage = CompilationStage::Done;
-        }));
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(cli::CliError::HelpRequested) => {
+            println!("{}", cli::USAGE);
+            return cli::exit_code(cli::EXIT_SUCCESS);
+        }
+        Err(cli::CliError::Usage(message)) => {
+            eprintln!("{} {}\n\n{}", red("error".to_string()), message, cli::USAGE);
+            return cli::exit_code(cli::EXIT_USAGE_ERROR);
+        }
+    };
+
+    let targets = options.targets();
+    let paths = match cli::collect_source_files(&options.inputs) {
+        Ok(paths) => paths,
+        Err(error) => {
+            eprintln!("{} could not read the input files: {}", red("error".to_string()), error);
+            return cli::exit_code(cli::EXIT_IO_ERROR);
+        }
+    };
+    //the files compile on their own threads, so this has to be caught before any of them gets written
+    if let (false, Some((first, second))) = (targets.is_empty(), cli::find_output_collision(&paths)) {
+        eprintln!(
+            "{} {} and {} would both be written to {}, rename one of them",
+            red("error".to_string()),
+            first.display(),
+            second.display(),
+            options.out_dir.join(first.file_stem().unwrap_or_default()).display()
+        );
+        return cli::exit_code(cli::EXIT_USAGE_ERROR);
+    }
+    let db = match load_sources(&paths) {
+        Ok(db) => db,
+        Err(error) => {
+            eprintln!("{} could not read the input files: {}", red("error".to_string()), error);
+            return cli::exit_code(cli::EXIT_IO_ERROR);
+        }
+    };
+    let files_to_compile: Vec<Mutex<File>> = db
+        .file_ids()
+        .map(|file| Mutex::new(File::new(Tokenizer::for_file(&db, file))))
+        .collect();
+
+    let results = std::thread::scope(|scope| {
+        let mut running_threads = vec![];
+        for FILE in files_to_compile.iter() {
+            let targets = &targets;
+            let out_dir = &options.out_dir;
+            running_threads.push(scope.spawn(move || {
+                let mut file = FILE.lock().unwrap();
+                //having different stages is so that when files can import from each other, they will be able to check to make sure that the file is done parsing before trying to use it
+                file.stage = CompilationStage::Parsing;
+                file.generate_syntax_tree_from_source_code();
+                file.stage = CompilationStage::TypeChecking;
+                file.type_check();
+                if file.tokenizer.diagnostics.has_errors() {
+                    //the syntax tree isnt trustworthy so nothing gets written for this file
+                    return Ok(());
+                }
+                file.stage = CompilationStage::CodeGeneration;
+                let written = file.output_code_from_syntax_tree(out_dir, targets);
+                file.stage = CompilationStage::Done;
+                written
+            }));
+        }
+        running_threads.into_iter().map(|thread| thread.join()).collect::<Vec<_>>()
+    });
+
+    let mut exit_code = cli::EXIT_SUCCESS;
+    let mut error_count = 0;
+    for FILE in files_to_compile.iter() {
+        //a file whose thread panicked leaves its mutex poisoned, its diagnostics are still worth showing
+        let file = FILE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match options.message_format {
+            cli::MessageFormat::Human => eprint!("{}", file.tokenizer.render_diagnostics()),
+            cli::MessageFormat::Json => print!("{}", file.tokenizer.render_diagnostics_json()),
+        }
+        error_count += file.tokenizer.diagnostics.error_count();
     }
-    for thread in running_threads {
-        thread.join().unwrap();
+    if error_count > 0 {
+        if options.message_format == cli::MessageFormat::Human {
+            eprintln!("{} could not compile due to {} error(s)", red("error".to_string()), error_count);
+        }
+        exit_code = cli::EXIT_COMPILE_ERROR;
+    }
+    for result in results {
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => {
+                eprintln!("{} could not write the output files: {}", red("error".to_string()), error);
+                exit_code = exit_code.max(cli::EXIT_IO_ERROR);
+            }
+            // the panic message was already printed by the panic hook
+            Err(_) => exit_code = exit_code.max(cli::EXIT_INTERNAL_ERROR),
+        }
     }
+    cli::exit_code(exit_code)
 }
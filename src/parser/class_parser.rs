@@ -1,72 +1,89 @@
 
+use crate::diagnostics::{ParseResult, Span};
 use crate::parser::expression::Expression;
 use crate::parser::expression::ExpressionPiece;
 use crate::parser::function_parser::Function;
 use crate::parser::type_parser::Type_;
-use crate::project_basic_utils::token::*;
+use crate::project_basic_utils::keyword::Keyword;
 use crate::project_basic_utils::tokenizer::*;
+use crate::symbol::Symbol;
 use crate::until;
-use crate::utils::red;
 
 use crate::comp;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
-pub struct Field {
-    pub name: &'static str,
-    pub type_: Type_,
-    pub default_value: Expression,
+pub struct Field<'src> {
+    pub name: Symbol<'src>,
+    pub type_: Type_<'src>,
+    pub default_value: Expression<'src>,
+    pub name_span: Span,
+    pub span: Span,
 }
 
-impl Field {
-    fn new(t: &mut Tokenizer) -> Self {
-        let type_ = Type_::new(t);
-        let name = t.expect(TokenType::IDENTIFIER);
-        if t.optionaly_expect_char('=') {
-            let default_value = Expression::new(t, '\n', '\n');
-            t.eat_all_spaces();
-            return Self {
+impl<'src> Field<'src> {
+    fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        let type_ = Type_::new(t)?;
+        let (name, name_span) = t.expect_name("field")?;
+        if t.eat("=") {
+            let default_value = Expression::new(t, '\n', '\n')?;
+            let span = t.span_from(type_.span.start);
+            return Ok(Self {
                 name,
                 type_,
                 default_value,
-            };
+                name_span,
+                span,
+            });
         } else {
-            t.eat_all_spaces();
-            return Self {
+            let span = t.span_from(type_.span.start);
+            return Ok(Self {
                 name,
                 type_,
-                default_value: Expression(ExpressionPiece::Placeholder(false)),
-            };
+                default_value: Expression(ExpressionPiece::Placeholder(false), Span::new(span.end, span.end)),
+                name_span,
+                span,
+            });
         }
     }
 }
 
-pub struct Class {
-    pub name: &'static str,
-    pub fields: Vec<Field>,
-    pub methods: Vec<Function>,
+pub struct Class<'src> {
+    pub name: Symbol<'src>,
+    pub fields: Vec<Field<'src>>,
+    pub methods: Vec<Function<'src>>,
+    pub name_span: Span,
+    //from the name to the closing }, the class keyword is eaten before we get here
+    pub span: Span,
 }
 
-impl Class {
-    pub fn new(t: &mut Tokenizer) -> Self {
-        Self::preview_scan(t);
+impl<'src> Class<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        Self::preview_scan(t)?;
+        let (name, name_span) = t.expect_name("class")?;
         let mut res = Self {
-            name: t.expect(TokenType::IDENTIFIER),
+            name,
             fields: vec![],
             methods: vec![],
+            name_span,
+            span: name_span,
         };
-        t.expect_char('{');
-        t.eat_all_spaces();
-        until!(t.optionaly_expect_char('}'); {
-            t.eat_all_spaces();
-            if t.peek_next_word() == "function" {
+        t.expect_symbol("{")?;
+        until!(t.eat("}"); {
+            if t.peek_keyword() == Some(Keyword::Function) {
                 t.next(); //eat up the 'function' keyword
-                res.methods.push(Function::new(t));
+                res.methods.push(Function::new(t)?);
             } else {
-                res.fields.push(Field::new(t));
+                let field_start = t.position;
+                match Field::new(t) {
+                    Ok(field) => res.fields.push(field),
+                    //panic mode recovery: skip the broken field and carry on with the next line of the class
+                    Err(error) if !t.in_range() => return Err(error),
+                    Err(_) => t.skip_rest_of_statement(field_start),
+                }
             }
-            t.eat_all_spaces(); 
         });
-        res
+        res.span = t.span_from(name_span.start);
+        Ok(res)
     }
 
     pub fn display(&self) {
@@ -77,41 +94,35 @@ impl Class {
         println!("}}");
     }
 
-    fn preview_scan(t: &mut Tokenizer) {
+    fn preview_scan(t: &mut Tokenizer) -> ParseResult<()> {
         use crate::previewScannerUtils::*;
         if !looks_like_identifier(t) {
             let next_token = t.next();
-            t.user_error(
-                next_token.start_index,
-                next_token.start_index + next_token.value.len(),
-            );
-            panic!("{}", red("expected identifier (class name)".to_string()));
+            return Err(t.unexpected(&next_token, "an identifier (class name)"));
         }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use super::*;
+    use crate::project_basic_utils::token::TokenType;
 
     #[test]
     fn test_class_parser() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "class Person{
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "class Person{
             int age
             string name
             string email
         }",
-            parse_index: 0,
-        };
+        );
 
-        if t.expect(TokenType::KEYWORD) == "class" {
-            let _class = Class::new(&mut t);
+        if t.expect(TokenType::KEYWORD).unwrap() == "class" {
+            let _class = Class::new(&mut t).unwrap();
             _class.display();
             assert_eq!(_class.name, "Person");
             assert_eq!(_class.fields.len(), 3);
@@ -1,6 +1,9 @@
+use crate::diagnostics::Span;
 use crate::parser::expression::Expression;
 use crate::parser::expression::FunctionCall;
 use crate::parser::var_parser::Var;
+use crate::symbol::Symbol;
+use std::ops::Range;
 
 /**
  *
@@ -9,22 +12,151 @@ use crate::parser::var_parser::Var;
  *
  */
 
-#[derive(Debug, Clone)]
-pub enum ValidInCodeBlock {
-    Expression(Expression),
-    FunctionCall(FunctionCall),
-    Var(Var),
+/// what comes between for and the {, the body and its JumpIndex come after the marker like they do for a while
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct ForHead<'src> {
+    //one for arrays, strings and ranges, the key and the value for maps
+    pub names: Vec<(Symbol<'src>, Span)>,
+    //a range is a call to ..
+    pub iterable: Expression<'src>,
+}
+
+/// `Shape.Circle(r)` at the start of an arm of a match, the bindings get the payload in the order the variant declares it.
+/// an arm without parentheses doesnt bind anything
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct VariantPattern<'src> {
+    pub enum_name: Symbol<'src>,
+    pub variant: Symbol<'src>,
+    pub bindings: Vec<(Symbol<'src>, Span)>,
+    //the names the enum gives the payload, the checker fills them in for the backends
+    pub payload_names: Vec<Symbol<'src>>,
+    //from the enum name to the end of the bindings
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum ValidInCodeBlock<'src> {
+    Expression(Expression<'src>),
+    FunctionCall(FunctionCall<'src>),
+    Var(Var<'src>),
     // ScopeJumpIndex(usize)
     //once i learned that the way to do it is in a stack like manner
     WhileStartMarker,
     IfStartMarker,
+    //else if and else come right after the JumpIndex of the if (or else if) before them, an else if has its condition next like an if does
+    ElseIfStartMarker,
+    ElseStartMarker,
+    ForStartMarker(ForHead<'src>),
+    //what gets matched, every arm is a block of its own inside the block of the match
+    MatchStartMarker(Expression<'src>),
+    //None for the _ arm, that one takes everything the arms before it didnt
+    MatchArmStartMarker(Option<VariantPattern<'src>>, Span),
+    //the span is the keyword, the value is None for a bare return
+    Return(Option<Expression<'src>>, Span),
+    Break(Span),
+    Continue(Span),
     HeadEndAndBodyStartMarker,
     JumpIndex(usize),
 }
 
-pub trait CodeBlock {
-    fn body_ptr(&mut self) -> &mut Vec<ValidInCodeBlock>;
-    fn get_body(&self) -> &Vec<ValidInCodeBlock>;
+/// the index of the JumpIndex that closes the block opened by the marker at start, blocks nested inside it get skipped over.
+/// tokens.len() if the block never got closed
+pub fn block_end(tokens: &[ValidInCodeBlock], start: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            ValidInCodeBlock::WhileStartMarker
+            | ValidInCodeBlock::IfStartMarker
+            | ValidInCodeBlock::ElseIfStartMarker
+            | ValidInCodeBlock::ElseStartMarker
+            | ValidInCodeBlock::ForStartMarker(_)
+            | ValidInCodeBlock::MatchStartMarker(_)
+            | ValidInCodeBlock::MatchArmStartMarker(..) => depth += 1,
+            ValidInCodeBlock::JumpIndex(_) if depth == 1 => return index,
+            ValidInCodeBlock::JumpIndex(_) => depth -= 1,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// the arms of the match opened by the marker at start, each with the range of tokens that makes up its body
+pub fn match_arms<'a, 'src>(tokens: &'a [ValidInCodeBlock<'src>], start: usize) -> Vec<(Option<&'a VariantPattern<'src>>, Range<usize>)> {
+    let end = block_end(tokens, start);
+    let mut arms = vec![];
+    let mut i = start + 1;
+    while i < end {
+        match &tokens[i] {
+            ValidInCodeBlock::MatchArmStartMarker(pattern, _) => {
+                let arm_end = block_end(tokens, i);
+                arms.push((pattern.as_ref(), i + 1..arm_end));
+                i = arm_end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    arms
+}
+
+/// whether running the tokens in range always ends in a return. a loop might not run at all so it doesnt count,
+/// an if only does when it has an else and every branch returns, a match when every arm does
+pub fn always_returns(tokens: &[ValidInCodeBlock], range: Range<usize>) -> bool {
+    let mut i = range.start;
+    while i < range.end {
+        match &tokens[i] {
+            ValidInCodeBlock::Return(..) => return true,
+            ValidInCodeBlock::IfStartMarker => {
+                let mut every_branch_returns = true;
+                let mut start = i;
+                loop {
+                    let end = block_end(tokens, start);
+                    every_branch_returns &= always_returns(tokens, start + 1..end);
+                    let is_else = matches!(tokens[start], ValidInCodeBlock::ElseStartMarker);
+                    i = end + 1;
+                    match tokens.get(i) {
+                        Some(ValidInCodeBlock::ElseIfStartMarker | ValidInCodeBlock::ElseStartMarker) if !is_else => start = i,
+                        _ if is_else && every_branch_returns => return true,
+                        _ => break,
+                    }
+                }
+            }
+            //a match that misses a variant is already an error of its own
+            ValidInCodeBlock::MatchStartMarker(_) => {
+                let arms = match_arms(tokens, i);
+                if !arms.is_empty() && arms.into_iter().all(|(_, body)| always_returns(tokens, body)) {
+                    return true;
+                }
+                i = block_end(tokens, i) + 1;
+            }
+            ValidInCodeBlock::WhileStartMarker
+            | ValidInCodeBlock::ElseIfStartMarker
+            | ValidInCodeBlock::ElseStartMarker
+            | ValidInCodeBlock::ForStartMarker(_)
+            | ValidInCodeBlock::MatchArmStartMarker(..) => i = block_end(tokens, i) + 1,
+            _ => i += 1,
+        }
+    }
+    false
+}
+
+impl<'src> ValidInCodeBlock<'src> {
+    /// the expressions the statement is made of, the ones nested inside of those are reached through Expression::walk_mut
+    pub fn expressions_mut(&mut self) -> Vec<&mut Expression<'src>> {
+        match self {
+            ValidInCodeBlock::Expression(expression) => vec![expression],
+            ValidInCodeBlock::FunctionCall(call) => call.params.iter_mut().collect(),
+            ValidInCodeBlock::Var(var) => vec![&mut var.default_value],
+            ValidInCodeBlock::ForStartMarker(head) => vec![&mut head.iterable],
+            ValidInCodeBlock::MatchStartMarker(subject) => vec![subject],
+            ValidInCodeBlock::Return(value, _) => value.iter_mut().collect(),
+            _ => vec![],
+        }
+    }
+}
+
+pub trait CodeBlock<'src> {
+    fn body_ptr(&mut self) -> &mut Vec<ValidInCodeBlock<'src>>;
+    fn get_body(&self) -> &Vec<ValidInCodeBlock<'src>>;
 
     fn contains_nested_bracket_scope(&self) -> bool {
         //bracket_scope is a scope defined by {}, (if, while, function, etc)
@@ -0,0 +1,107 @@
+use crate::diagnostics::{ParseResult, Span};
+use crate::parser::type_parser::Type_;
+use crate::project_basic_utils::tokenizer::*;
+use crate::symbol::Symbol;
+use crate::until;
+
+/// one case of an enum, its payload is written like params without defaults: `Circle(float radius)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Variant<'src> {
+    pub name: Symbol<'src>,
+    pub payload: Vec<(Symbol<'src>, Type_<'src>)>,
+    pub name_span: Span,
+}
+
+impl<'src> Variant<'src> {
+    fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        let (name, name_span) = t.expect_name("variant")?;
+        let mut payload = vec![];
+        if t.eat_on_same_line("(") {
+            until!(t.eat(")"); {
+                let type_ = Type_::new(t)?;
+                let (field_name, _) = t.expect_name("payload")?;
+                payload.push((field_name, type_));
+                t.expect_symbol_with_backups(",", &[")"])?;
+            });
+        }
+        Ok(Self { name, payload, name_span })
+    }
+}
+
+/// the variants go one per line or are split by commas, a value of the enum is always exactly one of them
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Enum<'src> {
+    pub name: Symbol<'src>,
+    pub variants: Vec<Variant<'src>>,
+    pub name_span: Span,
+    //from the name to the closing }, the enum keyword is eaten before we get here
+    pub span: Span,
+}
+
+impl<'src> Enum<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        Self::preview_scan(t)?;
+        let (name, name_span) = t.expect_name("enum")?;
+        let mut variants = vec![];
+        t.expect_symbol("{")?;
+        until!(t.eat("}"); {
+            let variant_start = t.position;
+            match Variant::new(t) {
+                Ok(variant) => variants.push(variant),
+                //panic mode recovery: skip the broken variant and carry on with the next line of the enum
+                Err(error) if !t.in_range() => return Err(error),
+                Err(_) => t.skip_rest_of_statement(variant_start),
+            }
+            t.eat(",");
+        });
+        Ok(Self {
+            name,
+            variants,
+            name_span,
+            span: t.span_from(name_span.start),
+        })
+    }
+
+    pub fn variant(&self, name: Symbol) -> Option<&Variant<'src>> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    fn preview_scan(t: &mut Tokenizer) -> ParseResult<()> {
+        use crate::previewScannerUtils::*;
+        if !looks_like_identifier(t) {
+            let next_token = t.next();
+            return Err(t.unexpected(&next_token, "an identifier (enum name)"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_basic_utils::token::TokenType;
+
+    #[test]
+    fn test_enum_parser() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "enum Shape {
+                Circle(float radius)
+                Rect(float w, float h), Empty
+            }",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "enum");
+        let _enum = Enum::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 0);
+        assert_eq!(_enum.name, "Shape");
+        let variants: Vec<(&str, Vec<&str>)> = _enum
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.payload.iter().map(|(name, _)| name.as_str()).collect()))
+            .collect();
+        assert_eq!(variants, vec![("Circle", vec!["radius"]), ("Rect", vec!["w", "h"]), ("Empty", vec![])]);
+        assert_eq!(_enum.variant(t.intern("Rect")).unwrap().payload[1].1.name, "float");
+    }
+}
@@ -1,62 +1,153 @@
+use crate::diagnostics::{ParseResult, Span};
 use crate::libs::linkedList;
-use crate::precedence_order::absorb_neighbors;
+use crate::parser::function_parser::Function;
+use crate::precedence_order::{absorb_neighbors, PREFIX_OPERATORS};
+use crate::previewScannerUtils::looks_like_map_literal;
+use crate::project_basic_utils::keyword::Keyword;
+use crate::project_basic_utils::string_literal;
 use crate::project_basic_utils::token::*;
 use crate::project_basic_utils::tokenizer::*;
+use crate::symbol::Symbol;
 
 use crate::comp;
 use crate::until;
-use crate::utils::red;
 
 // Define FunctionCall here since it's used in this module
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Ord, PartialOrd)]
-pub struct FunctionCall {
-    pub name: &'static str,
-    pub params: Vec<Expression>,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct FunctionCall<'src> {
+    pub name: Symbol<'src>,
+    pub params: Vec<Expression<'src>>,
 }
 
-pub fn operator_to_string(ep: &ExpressionPiece) -> &'static str {
+pub fn operator_to_string<'src>(ep: &ExpressionPiece<'src>) -> Symbol<'src> {
     if let ExpressionPiece::Operator(op) = ep {
-        op
+        *op
     } else {
         panic!("not an operator");
     }
 }
 
-impl FunctionCall {
-    fn new(name: &'static str, params: Vec<Expression>) -> Self {
+impl<'src> FunctionCall<'src> {
+    fn new(name: Symbol<'src>, params: Vec<Expression<'src>>) -> Self {
         Self { name, params }
     }
 }
 
+/// `object.name`, or `object.name(args)` for a method call
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct MemberAccess<'src> {
+    pub object: Box<Expression<'src>>,
+    pub name: Symbol<'src>,
+    pub name_span: Span,
+    //None for a field, a method call always has its (maybe empty) list
+    pub args: Option<Vec<Expression<'src>>>,
+}
+
+/// `Person{ id: 1, car: car }`, after type checking the fields are in the order the class declares them with the defaults filled in
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Construction<'src> {
+    pub class_name: Symbol<'src>,
+    pub class_span: Span,
+    pub fields: Vec<(Symbol<'src>, Span, Expression<'src>)>,
+}
+
+/// `Shape.Circle(1.5)` once the checker knows Shape is an enum, every value of the payload is paired with the name the variant gives it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct VariantValue<'src> {
+    pub enum_name: Symbol<'src>,
+    pub variant: Symbol<'src>,
+    pub payload: Vec<(Symbol<'src>, Expression<'src>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum NumberKind {
+    Int { radix: u32 },
+    Float,
+}
+
+impl NumberKind {
+    /// works on text the tokenizer already accepted as a number
+    pub fn of(text: &str) -> Self {
+        match text.get(..2) {
+            Some("0x" | "0X") => NumberKind::Int { radix: 16 },
+            Some("0o" | "0O") => NumberKind::Int { radix: 8 },
+            Some("0b" | "0B") => NumberKind::Int { radix: 2 },
+            _ if text.contains(['.', 'e', 'E']) => NumberKind::Float,
+            _ => NumberKind::Int { radix: 10 },
+        }
+    }
+
+    /// the digits without the radix prefix and the _ separators
+    pub fn digits(self, text: &str) -> String {
+        let digits = match self {
+            NumberKind::Int { radix: 10 } | NumberKind::Float => text,
+            NumberKind::Int { .. } => &text[2..],
+        };
+        digits.replace('_', "")
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
-pub enum ExpressionPiece {
-    FunctionCall(FunctionCall),
-    Variable(&'static str),
-    StringLiteral(&'static str),
-    NumberLiteral(&'static str),
-    Operator(&'static str),
+pub enum ExpressionPiece<'src> {
+    FunctionCall(FunctionCall<'src>),
+    MemberAccess(MemberAccess<'src>),
+    //the thing being indexed and the index (or key for a map)
+    Index(Box<Expression<'src>>, Box<Expression<'src>>),
+    //an Index the checker knows goes into a map, javascript has to use get and set for those
+    MapIndex(Box<Expression<'src>>, Box<Expression<'src>>),
+    ArrayLiteral(Vec<Expression<'src>>),
+    //the keys and values in the order they were written
+    MapLiteral(Vec<(Expression<'src>, Expression<'src>)>),
+    Construction(Construction<'src>),
+    //an anonymous function, its name is the function keyword
+    Lambda(Box<Function<'src>>),
+    VariantValue(VariantValue<'src>),
+    Variable(Symbol<'src>),
+    //already unescaped, the backends escape it again the way their language wants
+    StringLiteral(String),
+    //the text as it was written (with its prefix and _ separators), the backends turn it into something their language accepts
+    NumberLiteral(&'src str, NumberKind),
+    BoolLiteral(bool),
+    //a single quoted literal with exactly one char in it, anything else in single quotes is still a string
+    CharLiteral(char),
+    NullLiteral,
+    Operator(Symbol<'src>),
     Placeholder(bool),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 // ExpressionPiece is the what the parser uses internally, when you see ExpressionPiece getting passed around that means its not done making the syntax tree
-pub struct Expression(pub ExpressionPiece);
+// the span covers the whole expression, for a FunctionCall made out of an operator thats from the left operand to the right one
+pub struct Expression<'src>(pub ExpressionPiece<'src>, pub Span);
+
+impl<'src> Expression<'src> {
+    pub fn new(t: &mut Tokenizer<'src>, separator: char, scope_ender: char) -> ParseResult<Self> {
+        Self::parse(t, separator, scope_ender, true)
+    }
 
-impl Expression {
-    pub fn new(t: &mut Tokenizer, separator: char, scope_ender: char) -> Self {
+    /// stops at the separator like new but leaves it for the expression around this one to see,
+    /// the body of a => function ends there without being what the separator belongs to
+    pub fn new_before_separator(t: &mut Tokenizer<'src>, separator: char, scope_ender: char) -> ParseResult<Self> {
+        Self::parse(t, separator, scope_ender, false)
+    }
+
+    fn parse(t: &mut Tokenizer<'src>, separator: char, scope_ender: char, eats_separator: bool) -> ParseResult<Self> {
         //todo: remove the idea of the separator, where we eat up the token and just have tokens that when we wee we stop and dont take any action on. if we wanna eat that token wele do it form the call site
         use crate::libs::linkedList::*;
-        let mut tokens = LinkedList::new();
+        let mut tokens: LinkedList<Expression> = LinkedList::new();
 
+        let expression_start = t.position;
+        let expression_start_index = t.peek(0).span.start;
 
-        let expression_start_index = t.parse_index;
-        
         until!(
-            t.optionaly_expect_char(separator) || t.current_char() == scope_ender; {
-                tokens.append(parse_next_expression_piece(t));
+            ends_expression(t, expression_start, separator, scope_ender, eats_separator); {
+                let follows_value = tokens
+                    .tail
+                    .is_some_and(|tail| !matches!(tokens.storage[tail].value.0, ExpressionPiece::Operator(_)));
+                tokens.append(parse_next_expression_piece(t, follows_value, separator, scope_ender)?);
             }
         );
-        let expression_end_index = t.parse_index;
+        let expression_end_index = t.span_from(expression_start_index).end;
 
     
 
@@ -68,54 +159,322 @@ impl Expression {
 
         let mut current = tokens.head;
         while let Some(node_index) = current {
-            if let ExpressionPiece::Operator(op) = &tokens.storage[node_index].value {
+            if let ExpressionPiece::Operator(op) = &tokens.storage[node_index].value.0 {
                 let result_type = absorb_neighbors(&mut tokens, node_index);
-                match result_type {
-                    Ok(()) => {},
-                    Err(error) => {
-                        t.user_error(expression_start_index, expression_end_index);
-                        panic!("{}", red(error));
-                    }
+                if let Err(error) = result_type {
+                    return Err(t.user_error(expression_start_index, expression_end_index, error));
                 }
             }
             current = tokens.storage[node_index].next;
         }
 
         if tokens.len() == 0 {
-            return Self(ExpressionPiece::Placeholder(true));
+            return Ok(Self(ExpressionPiece::Placeholder(true), Span::new(expression_start_index, expression_start_index)));
+        }
+
+        Ok(tokens.storage[tokens.head.unwrap()].value.clone())
+    }
+
+    /// calls f on this expression and on every expression inside of it, the inner ones first
+    pub fn walk_mut(&mut self, f: &mut impl FnMut(&mut Expression<'src>)) {
+        match &mut self.0 {
+            ExpressionPiece::FunctionCall(call) => call.params.iter_mut().for_each(|param| param.walk_mut(f)),
+            ExpressionPiece::MemberAccess(access) => {
+                access.object.walk_mut(f);
+                access.args.iter_mut().flatten().for_each(|arg| arg.walk_mut(f));
+            }
+            ExpressionPiece::Index(object, index) | ExpressionPiece::MapIndex(object, index) => {
+                object.walk_mut(f);
+                index.walk_mut(f);
+            }
+            ExpressionPiece::ArrayLiteral(elements) => elements.iter_mut().for_each(|element| element.walk_mut(f)),
+            ExpressionPiece::MapLiteral(entries) => entries.iter_mut().for_each(|(key, value)| {
+                key.walk_mut(f);
+                value.walk_mut(f);
+            }),
+            ExpressionPiece::Construction(construction) => {
+                construction.fields.iter_mut().for_each(|(_, _, value)| value.walk_mut(f))
+            }
+            ExpressionPiece::VariantValue(value) => value.payload.iter_mut().for_each(|(_, value)| value.walk_mut(f)),
+            ExpressionPiece::Lambda(function) => {
+                function.params.iter_mut().for_each(|param| param.default_value.walk_mut(f));
+                let statements = function.body.iter_mut().flat_map(|statement| statement.expressions_mut());
+                statements.for_each(|expression| expression.walk_mut(f));
+            }
+            _ => {}
         }
+        f(self);
+    }
+}
 
-        Self(tokens.storage[tokens.head.unwrap()].value.clone())
+/// a \n separator or scope_ender is a line break before the next token (or the end of the file), its not a token so theres nothing to eat.
+/// ¥ means theres no separator, only the scope_ender stops the expression
+fn ends_expression(t: &mut Tokenizer, expression_start: usize, separator: char, scope_ender: char, eats_separator: bool) -> bool {
+    let at_line_end = t.position > expression_start && t.peek(0).newline_before || !t.in_range();
+    match separator {
+        '\n' if at_line_end => return true,
+        //a statement can still end at the } of a block on the same line, like `if a { return b }`
+        '\n' | '¥' => {}
+        _ => {
+            let mut buffer = [0; 4];
+            let separator = separator.encode_utf8(&mut buffer);
+            let found = if eats_separator { t.eat(separator) } else { t.at(separator) };
+            if found {
+                return true;
+            }
+        }
+    }
+    match scope_ender {
+        '\n' => at_line_end,
+        _ => t.at(scope_ender.encode_utf8(&mut [0; 4])),
     }
 }
 
-fn parse_next_expression_piece(t: &mut Tokenizer) -> ExpressionPiece {
+/// follows_value is whether the piece before this one is a value, an operator that doesnt follow one is a prefix operator.
+/// separator and scope_ender are the ones of the expression the piece is in
+fn parse_next_expression_piece<'src>(
+    t: &mut Tokenizer<'src>,
+    follows_value: bool,
+    separator: char,
+    scope_ender: char,
+) -> ParseResult<Expression<'src>> {
     let token = t.next();
+    if !follows_value && token.type_ == TokenType::OPERATOR && PREFIX_OPERATORS.contains(&token.value) {
+        //the operand is only the piece right after it (which can have its own prefix), so the prefix is already done when the binary operators get absorbed
+        let operand = parse_next_expression_piece(t, false, separator, scope_ender)?;
+        let piece = ExpressionPiece::FunctionCall(FunctionCall::new(t.intern(token.value), vec![operand]));
+        return Ok(Expression(piece, t.span_from(token.span.start)));
+    }
+    let piece = parse_piece_from_token(t, &token, separator, scope_ender)?;
+    //the span ends where the tokenizer stopped, for a function call thats after the )
+    let mut expression = Expression(piece, t.span_from(token.span.start));
+    if let ExpressionPiece::Operator(_) = expression.0 {
+        return Ok(expression);
+    }
+    //members and indexes bind tighter than anything else, so -a.b is -(a.b) and a.b[0].c is ((a.b)[0]).c
+    loop {
+        let piece = if t.eat(".") {
+            let (name, name_span) = t.expect_with_span(TokenType::IDENTIFIER)?;
+            let name = t.intern(name);
+            let args = if t.eat_on_same_line("(") {
+                Some(comp![Expression::new(t, ',', ')')?; until t.eat(")")])
+            } else {
+                None
+            };
+            ExpressionPiece::MemberAccess(MemberAccess { object: Box::new(expression), name, name_span, args })
+        } else if t.eat_on_same_line("[") {
+            //on the next line a [ starts an array literal
+            let open = t.tokens[t.position - 1].clone();
+            let index = parse_bracketed(t, &open, ']')?;
+            ExpressionPiece::Index(Box::new(expression), Box::new(index))
+        } else {
+            break;
+        };
+        expression = Expression(piece, t.span_from(token.span.start));
+    }
+    Ok(expression)
+}
+
+fn parse_piece_from_token<'src>(
+    t: &mut Tokenizer<'src>,
+    token: &Token<'src>,
+    separator: char,
+    scope_ender: char,
+) -> ParseResult<ExpressionPiece<'src>> {
     //dbg!(&token);
     //// dbg!(token);
+    if token.type_ == TokenType::KEYWORD {
+        match Keyword::from_word(token.value) {
+            Some(Keyword::True) => return Ok(ExpressionPiece::BoolLiteral(true)),
+            Some(Keyword::False) => return Ok(ExpressionPiece::BoolLiteral(false)),
+            Some(Keyword::Null) => return Ok(ExpressionPiece::NullLiteral),
+            Some(Keyword::Function) => {
+                let function = Function::new_anonymous(t, token, separator, scope_ender)?;
+                return Ok(ExpressionPiece::Lambda(Box::new(function)));
+            }
+            _ => {}
+        }
+    }
     if token.type_ == TokenType::IDENTIFIER {
-        if t.optionaly_expect_char('(') {
-            return ExpressionPiece::FunctionCall(FunctionCall::new(
-                token.value,
+        let name = t.intern(token.value);
+        if t.eat_on_same_line("(") {
+            return Ok(ExpressionPiece::FunctionCall(FunctionCall::new(
+                name,
                 comp![
-                    Expression::new(t, ',', ')');
-                    until t.optionaly_expect_char(')')
+                    Expression::new(t, ',', ')')?;
+                    until t.eat(")")
                 ],
-            ));
+            )));
+        } else if scope_ender != '{' && t.eat_on_same_line("{") {
+            //the { after the head of an if, while or for starts the body, so `if a == b {` doesnt construct a b. in parentheses it still can
+            let mut fields = vec![];
+            until!(t.eat("}"); {
+                let (field_name, name_span) = t.expect_with_span(TokenType::IDENTIFIER)?;
+                t.expect_symbol(":")?;
+                fields.push((t.intern(field_name), name_span, Expression::new(t, ',', '}')?));
+            });
+            return Ok(ExpressionPiece::Construction(Construction {
+                class_name: name,
+                class_span: token.span,
+                fields,
+            }));
         } else {
-            return ExpressionPiece::Variable(token.value);
+            return Ok(ExpressionPiece::Variable(name));
         }
     }
     if token.type_ == TokenType::STRING {
-        return ExpressionPiece::StringLiteral(token.value);
+        let value = string_literal::unescape(token.value).expect("the lexer only gives back string tokens with valid escapes");
+        let is_single_quoted = t.code[token.span.start..].starts_with('\'');
+        let mut chars = value.chars();
+        if let (true, Some(char), None) = (is_single_quoted, chars.next(), chars.next()) {
+            return Ok(ExpressionPiece::CharLiteral(char));
+        }
+        return Ok(ExpressionPiece::StringLiteral(value));
     }
     if token.type_ == TokenType::NUMBER {
-        return ExpressionPiece::NumberLiteral(token.value);
+        return Ok(ExpressionPiece::NumberLiteral(token.value, NumberKind::of(token.value)));
     }
     if token.type_ == TokenType::OPERATOR {
-        return ExpressionPiece::Operator(token.value);
+        return Ok(ExpressionPiece::Operator(t.intern(token.value)));
+    }
+    if token.type_ == TokenType::PUNCTUATION && token.value == "(" {
+        //the group is parsed all the way on its own, so to the operators around it its one value like a variable
+        return Ok(parse_bracketed(t, token, ')')?.0);
+    }
+    if token.type_ == TokenType::PUNCTUATION && token.value == "[" {
+        //[:] is an empty map and [] an empty array, otherwise a : after the first element makes it a map
+        if t.eat(":") {
+            t.expect_symbol("]")?;
+            return Ok(ExpressionPiece::MapLiteral(vec![]));
+        }
+        if looks_like_map_literal(t) {
+            let mut entries = vec![];
+            until!(t.eat("]"); {
+                let key = Expression::new(t, ':', ']')?;
+                entries.push((key, Expression::new(t, ',', ']')?));
+            });
+            return Ok(ExpressionPiece::MapLiteral(entries));
+        }
+        return Ok(ExpressionPiece::ArrayLiteral(comp![Expression::new(t, ',', ']')?; until t.eat("]")]));
+    }
+    Err(t.unexpected(token, "an expression"))
+}
+
+/// whats between open and the close that matches it, which gets eaten.
+/// stopping at a } keeps a missing close from taking the rest of the block with it
+fn parse_bracketed<'src>(t: &mut Tokenizer<'src>, open: &Token<'src>, close: char) -> ParseResult<Expression<'src>> {
+    let open_position = t.position - 1;
+    let inside = Expression::new(t, close, '}')?;
+    let count = |bracket: &str| {
+        t.tokens[open_position..t.position]
+            .iter()
+            .filter(|token| token.type_ == TokenType::PUNCTUATION && token.value == bracket)
+            .count()
+    };
+    if count(open.value) != count(close.encode_utf8(&mut [0; 4])) {
+        return Err(t.user_error(open.span.start, open.span.end, format!("this `{}` is never closed", open.value)));
+    }
+    if let ExpressionPiece::Placeholder(_) = inside.0 {
+        let brackets = if close == ')' { "parentheses" } else { "brackets" };
+        return Err(t.user_error(open.span.start, t.last_end, format!("expected an expression between the {}", brackets)));
+    }
+    Ok(inside)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// the tree an expression parses into, written out like (* (- a) b)
+    pub(crate) fn shape(code: &str) -> String {
+        let mut t = Tokenizer::new(file!(), line!() as usize, code);
+        let expression = Expression::new(&mut t, '\n', '\n').unwrap();
+        assert_eq!(t.diagnostics.error_count(), 0);
+        show(&expression)
+    }
+
+    fn show(expression: &Expression) -> String {
+        match &expression.0 {
+            ExpressionPiece::FunctionCall(call) => {
+                let params: Vec<String> = call.params.iter().map(show).collect();
+                format!("({} {})", call.name, params.join(" "))
+            }
+            ExpressionPiece::MemberAccess(access) => match &access.args {
+                Some(args) => {
+                    let args: Vec<String> = args.iter().map(show).collect();
+                    format!("(.{} {} [{}])", access.name, show(&access.object), args.join(" "))
+                }
+                None => format!("(.{} {})", access.name, show(&access.object)),
+            },
+            ExpressionPiece::Index(object, index) => format!("([] {} {})", show(object), show(index)),
+            ExpressionPiece::ArrayLiteral(elements) => {
+                format!("[{}]", elements.iter().map(show).collect::<Vec<String>>().join(" "))
+            }
+            ExpressionPiece::MapLiteral(entries) => {
+                let entries: Vec<String> = entries.iter().map(|(key, value)| format!("{}:{}", show(key), show(value))).collect();
+                format!("[{}]", entries.join(" "))
+            }
+            ExpressionPiece::Construction(construction) => {
+                let fields: Vec<String> = construction.fields.iter().map(|(name, _, value)| format!("{}:{}", name, show(value))).collect();
+                format!("{}{{{}}}", construction.class_name, fields.join(" "))
+            }
+            ExpressionPiece::Variable(name) => name.to_string(),
+            ExpressionPiece::NumberLiteral(number, _) => number.to_string(),
+            ExpressionPiece::BoolLiteral(value) => value.to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_prefix_operators() {
+        assert_eq!(shape("-a * b"), "(* (- a) b)");
+        assert_eq!(shape("a - -b"), "(- a (- b))");
+        assert_eq!(shape("x = !done"), "(= x (! done))");
+        assert_eq!(shape("- -1 + ~mask"), "(+ (- (- 1)) (~ mask))");
+    }
+
+    #[test]
+    fn test_parentheses() {
+        assert_eq!(shape("(a + b) * c"), "(* (+ a b) c)");
+        assert_eq!(shape("a * ((b + c) % (2))"), "(* a (% (+ b c) 2))");
+        assert_eq!(shape("-(a - b) - f((1), \")\")"), "(- (- (- a b)) (f 1 StringLiteral(\")\")))");
+
+        let mut t = Tokenizer::new(file!(), line!() as usize, "(a + b");
+        assert!(Expression::new(&mut t, '\n', '\n').is_err());
+    }
+
+    #[test]
+    fn test_member_access() {
+        assert_eq!(shape("person.car.engine.id"), "(.id (.engine (.car person)))");
+        assert_eq!(shape("-a.b * c.d(1, e.f)"), "(* (- (.b a)) (.d c [1 (.f e)]))");
+        assert_eq!(shape("(a + b).c().d"), "(.d (.c (+ a b) []))");
+    }
+
+    #[test]
+    fn test_indexes_and_container_literals() {
+        assert_eq!(shape("a[i + 1] = b.c[0][1] * -d[2]"), "(= ([] a (+ i 1)) (* ([] ([] (.c b) 0) 1) (- ([] d 2))))");
+        assert_eq!(shape("[1, [2, 3], []]"), "[1 [2 3] []]");
+        assert_eq!(shape("[a: [1], f(x, y): 2,]"), "[a:[1] (f x y):2]");
+        let mut t = Tokenizer::new(file!(), line!() as usize, "[:]");
+        assert_eq!(Expression::new(&mut t, '\n', '\n').unwrap().0, ExpressionPiece::MapLiteral(vec![]));
+
+        let mut t = Tokenizer::new(file!(), line!() as usize, "a[]");
+        assert!(Expression::new(&mut t, '\n', '\n').is_err());
+    }
+
+    #[test]
+    fn test_constructions() {
+        assert_eq!(shape("p = Person{ id: 1 + 2, car: Car{} }.id"), "(= p (.id Person{id:(+ 1 2) car:Car{}}))");
+        assert_eq!(shape("Person{\n    id: -a,\n    names: [b],\n}"), "Person{id:(- a) names:[b]}");
+
+        //in the head of an if the { starts the body
+        let mut t = Tokenizer::new(file!(), line!() as usize, "a == b { c }");
+        assert_eq!(show(&Expression::new(&mut t, '¥', '{').unwrap()), "(== a b)");
+        assert!(t.at("{"));
+        let mut t = Tokenizer::new(file!(), line!() as usize, "a == (B{ c: 1 }) {");
+        assert_eq!(show(&Expression::new(&mut t, '¥', '{').unwrap()), "(== a B{c:1})");
+
+        let mut t = Tokenizer::new(file!(), line!() as usize, "Person{ id 1 }");
+        assert!(Expression::new(&mut t, '\n', '\n').is_err());
     }
-    println!("about to show token");
-    //dbg!(&token);
-    todo!()
 }
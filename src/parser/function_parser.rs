@@ -1,64 +1,63 @@
 use std::string;
 
+use crate::diagnostics::{ParseError, ParseResult, Span};
 use crate::parser::code_block::CodeBlock;
-use crate::parser::code_block::ValidInCodeBlock;
+use crate::parser::code_block::{ForHead, ValidInCodeBlock, VariantPattern};
 use crate::parser::expression::Expression;
 use crate::parser::expression::ExpressionPiece;
 use crate::parser::type_parser::Type_;
 use crate::parser::var_parser::Var;
 
 use crate::previewScannerUtils::looks_like_type;
+use crate::project_basic_utils::keyword::Keyword;
 use crate::project_basic_utils::token::*;
 use crate::project_basic_utils::tokenizer::*;
-use crate::utils::red;
+use crate::symbol::Symbol;
 
 use crate::comp;
 
-#[derive(Debug)]
-pub struct Param {
-    pub name: &'static str,
-    pub type_: Type_,
-    pub default_value: Expression,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Param<'src> {
+    pub name: Symbol<'src>,
+    pub type_: Type_<'src>,
+    pub default_value: Expression<'src>,
+    pub name_span: Span,
+    pub span: Span,
 }
 
-impl Param {
-    fn new(t: &mut Tokenizer) -> Self {
-        Self::preview_scan(t);
-        let type_ = Type_::new(t);
-        let name = t.expect(TokenType::IDENTIFIER);
-        if t.optionaly_expect_char('=') {
-            let default_value = Expression::new(t, ',', ')');
-            t.eat_all_spaces();
-            return Self {
+impl<'src> Param<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        Self::preview_scan(t)?;
+        let type_ = Type_::new(t)?;
+        let (name, name_span) = t.expect_name("param")?;
+        if t.eat("=") {
+            let default_value = Expression::new(t, ',', ')')?;
+            let span = Span::new(type_.span.start, default_value.1.end);
+            return Ok(Self {
                 name,
                 type_,
                 default_value,
-            };
+                name_span,
+                span,
+            });
         } else {
-            t.expect_char_with_backups(',', &[')']);
-            t.eat_all_spaces();
-            return Self {
+            let span = Span::new(type_.span.start, name_span.end);
+            t.expect_symbol_with_backups(",", &[")"])?;
+            return Ok(Self {
                 name,
                 type_,
-                default_value: Expression(ExpressionPiece::Placeholder(false)),
-            };
+                default_value: Expression(ExpressionPiece::Placeholder(false), Span::new(span.end, span.end)),
+                name_span,
+                span,
+            });
         }
     }
-    fn preview_scan(t: &mut Tokenizer) {
-        t.eat_spaces();
-        // dbg!(t.current_char());
+    fn preview_scan(t: &mut Tokenizer) -> ParseResult<()> {
         if !looks_like_type(t) {
             let next_token = t.next();
-            t.user_error(
-                next_token.start_index,
-                next_token.start_index + next_token.value.len(),
-            );
-            panic!(
-                "{} {}",
-                red("expected type found".to_string()),
-                next_token.value
-            );
+            return Err(t.unexpected(&next_token, "a type"));
         }
+        Ok(())
     }
 }
 
@@ -68,102 +67,239 @@ use crate::parser::expression::FunctionCall;
 use crate::until;
 // we'e soon move this to its own file
 
-pub struct Function {
-    pub name: &'static str,
-    pub params: Vec<Param>,
-    pub body: Vec<ValidInCodeBlock>,
-    pub return_type: Type_,
+//the return type of a `=>` function that doesnt write one, the checker works it out from the expression
+pub const INFERRED_TYPE: &str = "_";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Function<'src> {
+    pub name: Symbol<'src>,
+    pub params: Vec<Param<'src>>,
+    pub body: Vec<ValidInCodeBlock<'src>>,
+    pub return_type: Type_<'src>,
+    pub name_span: Span,
+    //from the name to the closing }, the function keyword is eaten before we get here
+    pub span: Span,
 }
 
-impl Function {
-    pub fn new(t: &mut Tokenizer) -> Self {
-        Self::preview_scan(t);
-        let name = t.expect(TokenType::IDENTIFIER);
-        t.expect_char('(');
-        let params = comp![Param::new(t); until t.optionaly_expect_char(')')];
+impl<'src> Function<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        Self::preview_scan(t)?;
+        let (name, name_span) = t.expect_name("function")?;
+        t.expect_symbol("(")?;
+        let params = comp![Param::new(t)?; until t.eat(")")];
 
-        let return_type = if looks_like_type(t) {
-            Type_::new(t)
-        } else {
-            Type_ {
-                name: "void",
-                sub_types: Vec::new(),
-                is_optional: false,
-                is_pointer: false,
-            }
-        };
+        let return_type = Self::parse_return_type(t, "void")?;
 
         let mut res = Self {
             name,
             params,
             body: Vec::new(),
             return_type,
+            name_span,
+            span: name_span,
         };
-        res.parse_body(t);
-        res
+        res.parse_body(t)?;
+        res.span = t.span_from(name_span.start);
+        Ok(res)
     }
 
-    fn parse_body(&mut self, t: &mut Tokenizer) {
-        t.expect_char('{');
-        until!(t.optionaly_expect_char('}');{
-            let next_ident = t.peek_next_word();
-            match next_ident {
-                "if" => {
-                    t.next();
-                    self.body.push(ValidInCodeBlock::IfStartMarker);
-                    let cur_body_stack_pos = self.body.len()-1;
-                    if t.optionaly_expect_char('(') {
-                        let expression = Expression::new(t, ')', '{');
-                        self.body.push(ValidInCodeBlock::Expression(expression));
-                    }  else {
-                        let expression = Expression::new(t, '¥', '{');
-                        self.body.push(ValidInCodeBlock::Expression(expression));
-                    }
-                    self.parse_body(t);
-                    self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
+    /// `function(int a) int { ... }` as a value, or `function(int a) => a * 2` which gives back the expression after the =>.
+    /// the keyword is already eaten, it stands in for the name. the => form ends where the expression it is in ends, so it gets that ones separator and scope_ender
+    pub fn new_anonymous(t: &mut Tokenizer<'src>, keyword: &Token<'src>, separator: char, scope_ender: char) -> ParseResult<Self> {
+        t.expect_symbol("(")?;
+        let params = comp![Param::new(t)?; until t.eat(")")];
+        //a short function without a written return type gives back whatever its expression does
+        let return_type = Self::parse_return_type(t, if t.at("=>") { INFERRED_TYPE } else { "void" })?;
+        let mut res = Self {
+            name: t.intern(keyword.value),
+            params,
+            body: Vec::new(),
+            return_type,
+            name_span: keyword.span,
+            span: keyword.span,
+        };
+        if t.at("=>") {
+            let arrow = t.next();
+            //the separator belongs to the expression around this one, it has to see it to know the function is done
+            let value = Expression::new_before_separator(t, separator, scope_ender)?;
+            res.body.push(ValidInCodeBlock::Return(Some(value), arrow.span));
+        } else {
+            res.parse_body(t)?;
+        }
+        res.span = t.span_from(keyword.span.start);
+        Ok(res)
+    }
+
+    /// the type after the params, when theres nothing written its missing and it points at where it would go
+    fn parse_return_type(t: &mut Tokenizer<'src>, missing: &'static str) -> ParseResult<Type_<'src>> {
+        if looks_like_type(t) {
+            return Type_::new(t);
+        }
+        Ok(Type_ {
+            name: missing,
+            sub_types: Vec::new(),
+            is_optional: false,
+            is_pointer: false,
+            span: Span::new(t.last_end, t.last_end),
+        })
+    }
+
+    fn parse_body(&mut self, t: &mut Tokenizer<'src>) -> ParseResult<()> {
+        t.expect_symbol("{")?;
+        until!(t.eat("}");{
+            let statement_start = (self.body.len(), t.position);
+            if self.parse_statement(t).is_err() {
+                //panic mode recovery: drop what the broken statement left in the body and carry on from the next line
+                self.body.truncate(statement_start.0);
+                if !t.in_range() {
+                    return Err(ParseError);
                 }
-                "while" => {
+                t.skip_rest_of_statement(statement_start.1);
+            }
+        });
+        Ok(())
+    }
+
+    fn parse_statement(&mut self, t: &mut Tokenizer<'src>) -> ParseResult<()> {
+        match t.peek_keyword() {
+            Some(Keyword::If) => {
+                t.next();
+                self.parse_conditional_block(t, ValidInCodeBlock::IfStartMarker)?;
+                //else if and else chain onto the if, each one starts right after the JumpIndex of the one before it
+                while t.peek_keyword() == Some(Keyword::Else) {
                     t.next();
-                    self.body.push(ValidInCodeBlock::WhileStartMarker);
-                    let cur_body_stack_pos = self.body.len()-1;
-                    if t.optionaly_expect_char('(') {
-                        let expression = Expression::new(t, ')', '{');
-                        self.body.push(ValidInCodeBlock::Expression(expression));
-                    }  else {
-                        let expression = Expression::new(t, '¥', '{');
-                        self.body.push(ValidInCodeBlock::Expression(expression));
+                    if t.peek_keyword() == Some(Keyword::If) {
+                        t.next();
+                        self.parse_conditional_block(t, ValidInCodeBlock::ElseIfStartMarker)?;
+                    } else {
+                        self.body.push(ValidInCodeBlock::ElseStartMarker);
+                        let cur_body_stack_pos = self.body.len()-1;
+                        self.parse_body(t)?;
+                        self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
+                        break;
                     }
-                        self.parse_body(t);
-                    self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
                 }
-                "const" => {
-                    t.expect(TokenType::IDENTIFIER);
-                    self.body.push(ValidInCodeBlock::Var(Var::new(t)));
-                },
-                "let" => {
-                    t.expect(TokenType::IDENTIFIER);
-                    self.body.push(ValidInCodeBlock::Var(Var::new(t)));
+            }
+            Some(Keyword::Else) => {
+                let else_token = t.next();
+                return Err(t.user_error(else_token.span.start, else_token.span.end, "`else` without an `if` before it"));
+            }
+            Some(Keyword::While) => {
+                t.next();
+                self.parse_conditional_block(t, ValidInCodeBlock::WhileStartMarker)?;
+            }
+            Some(Keyword::For) => {
+                t.next();
+                let mut names = vec![t.expect_name("loop variable")?];
+                if t.eat(",") {
+                    names.push(t.expect_name("loop variable")?);
                 }
-                _ => {
-                    let expression = Expression::new(t, '\n', '}');
-                    self.body.push(ValidInCodeBlock::Expression(expression));
+                if t.peek_keyword() != Some(Keyword::In) {
+                    let next_token = t.peek(0).clone();
+                    return Err(t.unexpected(&next_token, "`in`"));
                 }
-
+                t.next();
+                let iterable = Expression::new(t, '¥', '{')?;
+                self.body.push(ValidInCodeBlock::ForStartMarker(ForHead { names, iterable }));
+                let cur_body_stack_pos = self.body.len()-1;
+                self.parse_body(t)?;
+                self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
+            }
+            Some(Keyword::Match) => {
+                t.next();
+                let subject = Expression::new(t, '¥', '{')?;
+                self.body.push(ValidInCodeBlock::MatchStartMarker(subject));
+                let cur_body_stack_pos = self.body.len()-1;
+                t.expect_symbol("{")?;
+                until!(t.eat("}"); {
+                    let arm_start = t.peek(0).span.start;
+                    let is_wildcard = t.peek(0).type_ == TokenType::IDENTIFIER && t.peek(0).value == "_";
+                    let pattern = if is_wildcard {
+                        t.next();
+                        None
+                    } else {
+                        Some(Self::parse_variant_pattern(t)?)
+                    };
+                    let arm_span = t.span_from(arm_start);
+                    t.expect_symbol("=>")?;
+                    self.body.push(ValidInCodeBlock::MatchArmStartMarker(pattern, arm_span));
+                    let arm_stack_pos = self.body.len()-1;
+                    self.parse_body(t)?;
+                    self.body.push(ValidInCodeBlock::JumpIndex(arm_stack_pos));
+                });
+                self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
+            }
+            Some(Keyword::Return) => {
+                let return_token = t.next();
+                //a return with nothing after it on its line gives back nothing
+                let value = if t.peek(0).newline_before || t.at("}") || !t.in_range() {
+                    None
+                } else {
+                    Some(Expression::new(t, '\n', '}')?)
+                };
+                self.body.push(ValidInCodeBlock::Return(value, return_token.span));
             }
-            t.eat_all_spaces();
+            Some(Keyword::Break) => {
+                let break_token = t.next();
+                self.body.push(ValidInCodeBlock::Break(break_token.span));
+            }
+            Some(Keyword::Continue) => {
+                let continue_token = t.next();
+                self.body.push(ValidInCodeBlock::Continue(continue_token.span));
+            }
+            Some(Keyword::Const | Keyword::Let) => {
+                t.expect(TokenType::KEYWORD)?;
+                self.body.push(ValidInCodeBlock::Var(Var::new(t)?));
+            }
+            _ => {
+                let expression = Expression::new(t, '\n', '}')?;
+                self.body.push(ValidInCodeBlock::Expression(expression));
+            }
+
+        }
+        Ok(())
+    }
 
+    /// `Shape.Circle(r, _)`, the variant is always written with its enum in front like when making a value of it
+    fn parse_variant_pattern(t: &mut Tokenizer<'src>) -> ParseResult<VariantPattern<'src>> {
+        let (enum_name, enum_span) = t.expect_name("enum")?;
+        t.expect_symbol(".")?;
+        let (variant, _) = t.expect_name("variant")?;
+        let mut bindings = vec![];
+        if t.eat_on_same_line("(") {
+            until!(t.eat(")"); {
+                bindings.push(t.expect_name("binding")?);
+                t.expect_symbol_with_backups(",", &[")"])?;
+            });
+        }
+        Ok(VariantPattern {
+            enum_name,
+            variant,
+            bindings,
+            payload_names: vec![],
+            span: t.span_from(enum_span.start),
         })
     }
-    fn preview_scan(t: &mut Tokenizer) {
+
+    /// the marker, the condition and then the body, for if, else if and while
+    fn parse_conditional_block(&mut self, t: &mut Tokenizer<'src>, marker: ValidInCodeBlock<'src>) -> ParseResult<()> {
+        self.body.push(marker);
+        let cur_body_stack_pos = self.body.len()-1;
+        //parentheses around the condition are just a group in the expression
+        let expression = Expression::new(t, '¥', '{')?;
+        self.body.push(ValidInCodeBlock::Expression(expression));
+        self.parse_body(t)?;
+        self.body.push(ValidInCodeBlock::JumpIndex(cur_body_stack_pos));
+        Ok(())
+    }
+
+    fn preview_scan(t: &mut Tokenizer) -> ParseResult<()> {
         use crate::previewScannerUtils::looks_like_identifier;
         if !looks_like_identifier(t) {
             let next_token = t.next();
-            t.user_error(
-                next_token.start_index,
-                next_token.start_index + next_token.value.len(),
-            );
-            panic!("{}", red("expected identifier (function name)".to_string()));
+            return Err(t.unexpected(&next_token, "an identifier (function name)"));
         }
+        Ok(())
     }
     pub fn display(&self) {
         println!("Function {} (", self.name);
@@ -191,6 +327,30 @@ impl Function {
                 ValidInCodeBlock::IfStartMarker => {
                     print!("if (\n");
                 }
+                ValidInCodeBlock::ElseIfStartMarker => {
+                    println!("else if (");
+                }
+                ValidInCodeBlock::ElseStartMarker => {
+                    println!("else {}", '{');
+                }
+                ValidInCodeBlock::ForStartMarker(head) => {
+                    println!("for {:?}", head);
+                }
+                ValidInCodeBlock::MatchStartMarker(subject) => {
+                    println!("match {:?}", subject);
+                }
+                ValidInCodeBlock::MatchArmStartMarker(pattern, _) => {
+                    println!("{:?} =>", pattern);
+                }
+                ValidInCodeBlock::Return(value, _) => {
+                    println!("return {:?}", value);
+                }
+                ValidInCodeBlock::Break(_) => {
+                    println!("break");
+                }
+                ValidInCodeBlock::Continue(_) => {
+                    println!("continue");
+                }
                 ValidInCodeBlock::HeadEndAndBodyStartMarker => {
                     print!("){}\n", '{');
                 }
@@ -201,40 +361,38 @@ impl Function {
     }
 }
 
-impl CodeBlock for Function {
-    fn get_body(&self) -> &Vec<ValidInCodeBlock> {
+impl<'src> CodeBlock<'src> for Function<'src> {
+    fn get_body(&self) -> &Vec<ValidInCodeBlock<'src>> {
         &self.body
     }
-    fn body_ptr(&mut self) -> &mut Vec<ValidInCodeBlock> {
+    fn body_ptr(&mut self) -> &mut Vec<ValidInCodeBlock<'src>> {
         &mut self.body
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use super::*;
+    use crate::diagnostics::codes;
+    use crate::parser::code_block::{block_end, match_arms};
 
     #[test]
     fn test_function_parser() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "
             
             function sub(int a, int b){}
 
 
 
             ",
-            parse_index: 0,
-        };
+        );
 
-        assert_eq!(t.expect(TokenType::KEYWORD), "function");
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
 
-        let _function = Function::new(&mut t);
+        let _function = Function::new(&mut t).unwrap();
         assert_eq!(_function.name, "sub");
 
         assert_eq!(_function.params.len(), 2);
@@ -248,21 +406,19 @@ mod tests {
 
     #[test]
     fn test_function_parser_that_having_default_values_dont_break_it() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "function sub(int a = 9, int b = 2 + 3){}
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function sub(int a = 9, int b = 2 + 3){}
 
 
 
             ",
-            parse_index: 0,
-        };
+        );
 
-        assert_eq!(t.expect(TokenType::KEYWORD), "function");
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
 
-        let _function = Function::new(&mut t);
+        let _function = Function::new(&mut t).unwrap();
         assert_eq!(_function.name, "sub");
 
         assert_eq!(_function.params.len(), 2);
@@ -275,11 +431,10 @@ mod tests {
     }
     #[test]
     fn test_that_parsing_function_body_doesnt_panic() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "function sub(int a = 9, int b = 2 + 3){
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function sub(int a = 9, int b = 2 + 3){
                 const int a = 9
                 let int b = 2
                 a = b+9
@@ -288,12 +443,11 @@ mod tests {
 
 
             ",
-            parse_index: 0,
-        };
+        );
 
-        assert_eq!(t.expect(TokenType::KEYWORD), "function");
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
 
-        let _function = Function::new(&mut t);
+        let _function = Function::new(&mut t).unwrap();
         assert_eq!(_function.name, "sub");
 
         assert_eq!(_function.params.len(), 2);
@@ -304,4 +458,237 @@ mod tests {
         assert_eq!(_function.params[1].name, "b");
         _function.display();
     }
+
+    #[test]
+    fn test_reserved_words_cant_be_names() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function while(int return){
+                let int match = 2
+                let int if_done = 1
+                let int iffy = 2
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 3);
+        assert!(t.diagnostics.diagnostics.iter().all(|diagnostic| diagnostic.code == codes::RESERVED_WORD));
+        //names that only start with a keyword are fine and dont get mistaken for an if
+        assert_eq!(_function.body.len(), 3);
+    }
+
+    #[test]
+    fn test_broken_statements_dont_stop_the_rest_of_the_body() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function sub(int a){
+                let int b = a +
+                while (a + ) {
+                    a = 2
+                }
+                a = b
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 2);
+        //only `a = b` survives, the broken while gets skipped along with its body
+        assert_eq!(_function.body.len(), 1);
+    }
+
+    #[test]
+    fn test_else_if_chains() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function sign(int a){
+                if a < 0 {
+                    a = 1
+                } else if a == 0 {
+                    a = 2
+                }
+                else {
+                    a = 3
+                }
+                else {
+                }
+                a = 4
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        //the second else has no if to belong to
+        assert_eq!(t.diagnostics.error_count(), 1);
+        let markers: Vec<&str> = _function
+            .body
+            .iter()
+            .map(|statement| match statement {
+                ValidInCodeBlock::IfStartMarker => "if",
+                ValidInCodeBlock::ElseIfStartMarker => "else if",
+                ValidInCodeBlock::ElseStartMarker => "else",
+                ValidInCodeBlock::JumpIndex(_) => "}",
+                _ => "statement",
+            })
+            .collect();
+        assert_eq!(
+            markers,
+            vec![
+                "if", "statement", "statement", "}",
+                "else if", "statement", "statement", "}",
+                "else", "statement", "}",
+                "statement",
+            ]
+        );
+        assert_eq!(crate::parser::code_block::block_end(&_function.body, 0), 3);
+    }
+
+    #[test]
+    fn test_for_in_loops() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function sum(int n){
+                for key, value in ages {
+                    n = n + value
+                }
+                for i in 0..n {
+                }
+                for x of nums {
+                }
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        //`of` instead of `in`
+        assert_eq!(t.diagnostics.error_count(), 1);
+        let Some(ValidInCodeBlock::ForStartMarker(head)) = _function.body.first() else {
+            panic!("expected a for, got {:?}", _function.body.first());
+        };
+        assert_eq!(head.names.iter().map(|(name, _)| *name).collect::<Vec<_>>(), vec!["key", "value"]);
+        assert_eq!(head.iterable.0, ExpressionPiece::Variable(t.intern("ages")));
+        let Some(ValidInCodeBlock::ForStartMarker(range)) = _function.body.get(3) else {
+            panic!("expected a for, got {:?}", _function.body.get(3));
+        };
+        assert!(matches!(&range.iterable.0, ExpressionPiece::FunctionCall(call) if call.name == ".."));
+        assert_eq!(crate::parser::code_block::block_end(&_function.body, 0), 2);
+    }
+
+    #[test]
+    fn test_return_break_and_continue() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function find(int n) int {
+                while n > 0 {
+                    if n == 3 { break }
+                    continue
+                }
+                if n == 1 { return n }
+                return
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 0);
+        let statements: Vec<String> = _function
+            .body
+            .iter()
+            .filter_map(|statement| match statement {
+                ValidInCodeBlock::Return(Some(value), _) => Some(format!("return {:?}", value.0)),
+                ValidInCodeBlock::Return(None, _) => Some("return".to_string()),
+                ValidInCodeBlock::Break(_) => Some("break".to_string()),
+                ValidInCodeBlock::Continue(_) => Some("continue".to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(statements, vec!["break", "continue", "return Variable(\"n\")", "return"]);
+    }
+
+    #[test]
+    fn test_anonymous_functions() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function main() {
+                let function<(int), int> f = function(int a) int {
+                    return a * 2
+                }
+                apply(function(int a, int b) => a + b, 3)
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 0);
+        let ValidInCodeBlock::Var(var) = &_function.body[0] else {
+            panic!("expected a var");
+        };
+        let ExpressionPiece::Lambda(block) = &var.default_value.0 else {
+            panic!("expected a function");
+        };
+        assert_eq!(block.params.len(), 1);
+        assert_eq!(block.return_type.name, "int");
+        assert!(matches!(block.body[..], [ValidInCodeBlock::Return(Some(_), _)]));
+
+        let ValidInCodeBlock::Expression(Expression(ExpressionPiece::FunctionCall(apply), _)) = &_function.body[1] else {
+            panic!("expected a call");
+        };
+        //the , after the short function still separates the args
+        assert_eq!(apply.params.len(), 2);
+        assert_eq!(apply.params[1].0, ExpressionPiece::NumberLiteral("3", crate::parser::expression::NumberKind::Int { radix: 10 }));
+        let ExpressionPiece::Lambda(short) = &apply.params[0].0 else {
+            panic!("expected a function");
+        };
+        assert_eq!(short.params.iter().map(|param| param.name).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(short.return_type.name, INFERRED_TYPE);
+        let [ValidInCodeBlock::Return(Some(Expression(ExpressionPiece::FunctionCall(sum), _)), _)] = &short.body[..] else {
+            panic!("expected the short function to return its expression");
+        };
+        assert_eq!(sum.name, "+");
+    }
+
+    #[test]
+    fn test_match_statements() {
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "function area(Shape s) float {
+                match s {
+                    Shape.Circle(r) => { return r * r }
+                    Shape.Empty => { }
+                    _ => {
+                        return 0.0
+                    }
+                }
+            }
+            ",
+        );
+
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "function");
+        let _function = Function::new(&mut t).unwrap();
+        assert_eq!(t.diagnostics.error_count(), 0);
+        assert!(matches!(&_function.body[0], ValidInCodeBlock::MatchStartMarker(Expression(ExpressionPiece::Variable(name), _)) if *name == "s"));
+        let arms = match_arms(&_function.body, 0);
+        let patterns: Vec<Option<(&str, &str, usize)>> = arms
+            .iter()
+            .map(|(pattern, _)| pattern.map(|pattern| (pattern.enum_name.as_str(), pattern.variant.as_str(), pattern.bindings.len())))
+            .collect();
+        assert_eq!(patterns, vec![Some(("Shape", "Circle", 1)), Some(("Shape", "Empty", 0)), None]);
+        assert!(matches!(_function.body[arms[0].1.clone()], [ValidInCodeBlock::Return(Some(_), _)]));
+        assert!(arms[1].1.is_empty());
+        assert_eq!(block_end(&_function.body, 0), _function.body.len() - 1);
+    }
 }
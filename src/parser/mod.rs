@@ -1,6 +1,7 @@
 pub mod If_parser;
 pub mod class_parser;
 pub mod code_block;
+pub mod enum_parser;
 pub mod expression;
 pub mod function_parser;
 pub mod type_parser;
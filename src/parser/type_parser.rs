@@ -1,106 +1,153 @@
+use crate::diagnostics::{ParseResult, Span};
 use crate::parser::expression::Expression;
+use crate::project_basic_utils::keyword::Keyword;
 use crate::project_basic_utils::token::TokenType;
 use crate::project_basic_utils::tokenizer::Tokenizer;
 use crate::until;
 use crate::utils::{blue, green};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Ord, PartialOrd)]
-pub struct Type_ {
-    pub name: &'static str,
-    pub sub_types: Vec<Type_>,
+pub struct Type_<'src> {
+    pub name: &'src str,
+    pub sub_types: Vec<Type_<'src>>,
     pub is_optional: bool,
     pub is_pointer: bool,
+    pub span: Span,
 }
 
-impl Type_ {
-    pub fn new(t: &mut Tokenizer) -> Self {
-        let is_pointer = t.optionaly_expect_char('*');
-        if t.optionaly_expect_char('[') {
-            if t.optionaly_expect_char(']') {
+impl<'src> Type_<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        let start = t.peek(0).span.start;
+        let mut res = Self::parse_without_span(t)?;
+        res.span = t.span_from(start);
+        Ok(res)
+    }
+
+    fn parse_without_span(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        let is_pointer = t.eat("*");
+        if t.eat("[") {
+            if t.eat("]") {
                 //@example: []int which is an array of ints
-                return Self {
+                return Ok(Self {
                     name: "array",
-                    sub_types: vec![Type_::new(t)],
-                    is_optional: t.optionaly_expect_char('?'),
+                    sub_types: vec![Type_::new(t)?],
+                    is_optional: t.eat("?"),
                     is_pointer,
-                };
+                    span: Span::default(),
+                });
             } else {
                 //@example: [string]int which is a map of strings to ints
-                let key_type = Type_::new(t);
-                t.expect_char(']');
+                let key_type = Type_::new(t)?;
+                t.expect_symbol("]")?;
                 let res = Self {
                     name: "map",
-                    sub_types: vec![key_type, Type_::new(t)],
-                    is_optional: t.optionaly_expect_char('?'),
+                    sub_types: vec![key_type, Type_::new(t)?],
+                    is_optional: t.eat("?"),
                     is_pointer,
+                    span: Span::default(),
                 };
-                return res;
+                return Ok(res);
             }
         }
 
-        if t.optionaly_expect_char('(') {
+        if t.eat("(") {
             let mut res = Self {
                 name: "tuple",
                 sub_types: vec![],
                 is_optional: false,
                 is_pointer,
+                span: Span::default(),
             };
-            until!(t.optionaly_expect_char(')');{
-                res.sub_types.push(Type_::new(t));
-                t.optionaly_expect_char(',');
+            until!(t.eat(")");{
+                res.sub_types.push(Type_::new(t)?);
+                t.eat(",");
             });
-            if t.optionaly_expect_char('?') {
+            if t.eat("?") {
                 res.is_optional = true;
             }
-            return res;
+            return Ok(res);
         }
 
+        //function<(params), return> is the type of a function, the keyword doubles as the name of the type
+        let name = if t.peek_keyword() == Some(Keyword::Function) {
+            t.next().value
+        } else {
+            t.expect(TokenType::IDENTIFIER)?
+        };
         let mut res = Self {
-            name: t.expect(TokenType::IDENTIFIER),
+            name,
             sub_types: vec![],
             is_optional: false,
             is_pointer,
+            span: Span::default(),
         };
-        if t.optionaly_expect_char('<') {
-            until!(t.optionaly_expect_char('>'); {
-                res.sub_types.push(Type_::new(t));
-                t.optionaly_expect_char(',');
+        if t.eat("<") {
+            until!(t.eat_split(">"); {
+                res.sub_types.push(Type_::new(t)?);
+                t.eat(",");
             });
         }
-        if t.optionaly_expect_char('?') {
+        if t.eat("?") {
             res.is_optional = true;
         }
-        res
+        Ok(res)
+    }
+    /// compares what the types are, not where they were written
+    pub fn same_type(&self, other: &Type_<'_>) -> bool {
+        self.name == other.name
+            && self.is_optional == other.is_optional
+            && self.is_pointer == other.is_pointer
+            && self.sub_types.len() == other.sub_types.len()
+            && self
+                .sub_types
+                .iter()
+                .zip(&other.sub_types)
+                .all(|(a, b)| a.same_type(b))
     }
+
     pub fn display(&self) {
         println!("{}", self.to_string());
     }
 
     pub fn to_string(&self) -> String {
+        self.format_with(correct_coloring)
+    }
+
+    /// same as to_string but without the terminal colors, for diagnostics that can end up in json
+    pub fn to_plain_string(&self) -> String {
+        self.format_with(|name| name.to_string())
+    }
+
+    fn format_with(&self, color: fn(&str) -> String) -> String {
+        //a tuple is written as its types in parentheses, thats also how the params of a function type are written
+        if self.name == "tuple" {
+            let types: Vec<String> = self.sub_types.iter().map(|sub_type| sub_type.format_with(color)).collect();
+            return format!("({})", types.join(", "));
+        }
         match self.sub_types.len() {
-            0 => return correct_coloring(&self.name),
+            0 => return color(self.name),
             1 => {
                 return format!(
                     "{}<{}>",
-                    correct_coloring(&self.name),
-                    self.sub_types[0].to_string()
+                    color(self.name),
+                    self.sub_types[0].format_with(color)
                 );
             }
             2 => {
                 return format!(
                     "{}<{}, {}>",
-                    correct_coloring(&self.name),
-                    self.sub_types[0].to_string(),
-                    self.sub_types[1].to_string()
+                    color(self.name),
+                    self.sub_types[0].format_with(color),
+                    self.sub_types[1].format_with(color)
                 );
             }
             _ => {
                 return format!(
                     "{}<{}, {}, {}> optional = {}",
-                    correct_coloring(&self.name),
-                    self.sub_types[0].to_string(),
-                    self.sub_types[1].to_string(),
-                    self.sub_types[2].to_string(),
+                    color(self.name),
+                    self.sub_types[0].format_with(color),
+                    self.sub_types[1].format_with(color),
+                    self.sub_types[2].format_with(color),
                     self.is_optional
                 );
             }
@@ -115,27 +162,23 @@ fn correct_coloring(s: &str) -> String {
         return green(&s.to_string());
     }
 }
-static BUILTINS: [&str; 5] = ["int", "string", "char", "bool", "void"];
+static BUILTINS: [&str; 6] = ["int", "float", "string", "char", "bool", "void"];
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use super::*;
 
     #[test]
     fn basic_test() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "
             Person<(int, char), [int]string<char>, []int>?
             ",
-            parse_index: 0,
-        };
+        );
 
-        let _type = Type_::new(&mut t);
+        let _type = Type_::new(&mut t).unwrap();
         _type.display();
 
         assert_eq!(_type.name, "Person");
@@ -151,4 +194,18 @@ mod tests {
         assert_eq!(_type.sub_types[2].name, "array");
         assert_eq!(_type.sub_types[2].sub_types[0].name, "int");
     }
+
+    #[test]
+    fn test_nested_generics_close_on_one_token() {
+        let code = "Map<int, Box<int>>? rest";
+        let mut t = Tokenizer::new(file!(), line!() as usize, code);
+
+        let _type = Type_::new(&mut t).unwrap();
+        assert_eq!(_type.sub_types[1].name, "Box");
+        assert_eq!(_type.sub_types[1].sub_types[0].name, "int");
+        assert!(_type.is_optional);
+        assert_eq!(&code[_type.sub_types[1].span.start.._type.sub_types[1].span.end], "Box<int>");
+        assert_eq!(&code[_type.span.start.._type.span.end], "Map<int, Box<int>>?");
+        assert_eq!(t.peek(0).value, "rest");
+    }
 }
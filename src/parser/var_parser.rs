@@ -1,52 +1,57 @@
+use crate::diagnostics::{ParseResult, Span};
 use crate::parser::expression::Expression;
 use crate::parser::expression::ExpressionPiece;
 use crate::parser::type_parser::Type_;
-use crate::project_basic_utils::token::*;
 use crate::project_basic_utils::tokenizer::*;
-use crate::utils::red;
+use crate::symbol::Symbol;
 
-#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
-pub struct Var {
-    pub name: &'static str,
-    pub type_: Type_,
-    pub default_value: Expression,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Var<'src> {
+    pub name: Symbol<'src>,
+    pub type_: Type_<'src>,
+    pub default_value: Expression<'src>,
+    pub name_span: Span,
+    //from the type to the end of the default value
+    pub span: Span,
 }
 
-impl Var {
-    pub fn new(t: &mut Tokenizer) -> Self {
-        Self::preview_scan(t);
-        let type_ = Type_::new(t);
-        let name = t.expect(TokenType::IDENTIFIER);
-        if t.optionaly_expect_char('=') {
-            let default_value = Expression::new(t, '\n', '}'); //} is bc for now this appears in a function body wich ends with }
-            t.eat_all_spaces();
-            return Self {
+impl<'src> Var<'src> {
+    pub fn new(t: &mut Tokenizer<'src>) -> ParseResult<Self> {
+        Self::preview_scan(t)?;
+        let type_ = Type_::new(t)?;
+        let (name, name_span) = t.expect_name("variable")?;
+        if t.eat("=") {
+            let default_value = Expression::new(t, '\n', '}')?; //} is bc for now this appears in a function body wich ends with }
+            let span = t.span_from(type_.span.start);
+            return Ok(Self {
                 name,
                 type_,
                 default_value,
-            };
+                name_span,
+                span,
+            });
         } else {
-            if t.current_char() != '\n' {
-                t.expect_char('\n');
+            if !t.peek(0).newline_before && t.in_range() {
+                let next_token = t.peek(0).clone();
+                return Err(t.unexpected(&next_token, "a new line"));
             }
-            t.eat_all_spaces();
-            return Self {
+            let span = t.span_from(type_.span.start);
+            return Ok(Self {
                 name,
                 type_,
-                default_value: Expression(ExpressionPiece::Placeholder(false)),
-            };
+                default_value: Expression(ExpressionPiece::Placeholder(false), Span::new(span.end, span.end)),
+                name_span,
+                span,
+            });
         }
     }
-    fn preview_scan(t: &mut Tokenizer) {
+    fn preview_scan(t: &mut Tokenizer) -> ParseResult<()> {
         use crate::previewScannerUtils::*;
         if !looks_like_type(t) {
             let next_token = t.next();
-            t.user_error(
-                next_token.start_index,
-                next_token.start_index + next_token.value.len(),
-            );
-            panic!("{}", red("expected type".to_string()));
+            return Err(t.unexpected(&next_token, "a type"));
         }
+        Ok(())
     }
     pub fn display(&self) {
         println!("Var {} {}", self.type_.to_string(), self.name);
@@ -55,71 +60,138 @@ impl Var {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Mutex;
-
     use super::*;
     use crate::parser::expression::Expression;
-    use crate::parser::expression::ExpressionPiece;
+    use crate::parser::expression::{ExpressionPiece, NumberKind};
     use crate::parser::type_parser::Type_;
     use crate::project_basic_utils::token::TokenType;
     use crate::project_basic_utils::tokenizer::Tokenizer;
 
     #[test]
     fn test_var_with_default_value() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "int a = 42\n",
-            parse_index: 0,
-        };
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "int a = 42\n",
+        );
 
-        let var = Var::new(&mut t);
+        let var = Var::new(&mut t).unwrap();
         assert_eq!(var.name, "a");
         assert_eq!(var.type_.name, "int");
         // The default_value should not be a placeholder
         match &var.default_value {
-            Expression(ExpressionPiece::Placeholder(false)) => panic!("Should not be placeholder"),
+            Expression(ExpressionPiece::Placeholder(false), _) => panic!("Should not be placeholder"),
             _ => {}
         }
     }
 
     #[test]
     fn test_var_without_default_value() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "int b\n",
-            parse_index: 0,
-        };
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "int b\n",
+        );
 
-        let var = Var::new(&mut t);
+        let var = Var::new(&mut t).unwrap();
         assert_eq!(var.name, "b");
         assert_eq!(var.type_.name, "int");
         // The default_value should be a placeholder
         match &var.default_value {
-            Expression(ExpressionPiece::Placeholder(false)) => {}
+            Expression(ExpressionPiece::Placeholder(false), _) => {}
             _ => panic!("Should be placeholder"),
         }
     }
 
+    #[test]
+    fn test_var_spans() {
+        let code = "[]int  nums = a + 42  \n";
+        let mut t = Tokenizer::new(file!(), line!() as usize, code);
+
+        let var = Var::new(&mut t).unwrap();
+        assert_eq!(&code[var.span.start..var.span.end], "[]int  nums = a + 42");
+        assert_eq!(&code[var.type_.span.start..var.type_.span.end], "[]int");
+        assert_eq!(&code[var.type_.sub_types[0].span.start..var.type_.sub_types[0].span.end], "int");
+        assert_eq!(&code[var.name_span.start..var.name_span.end], "nums");
+        let Expression(ExpressionPiece::FunctionCall(add), span) = &var.default_value else {
+            panic!("a + 42 should be a call to +");
+        };
+        assert_eq!(&code[span.start..span.end], "a + 42");
+        assert_eq!(&code[add.params[1].1.start..add.params[1].1.end], "42");
+    }
+
+    #[test]
+    fn test_unicode_names_and_strings() {
+        let code = "string größe_ü = 'naïve 日本語'\n";
+        let mut t = Tokenizer::new(file!(), line!() as usize, code);
+
+        let var = Var::new(&mut t).unwrap();
+        assert_eq!(var.name, "größe_ü");
+        assert_eq!(var.default_value.0, ExpressionPiece::StringLiteral("naïve 日本語".to_string()));
+        assert_eq!(&code[var.default_value.1.start..var.default_value.1.end], "'naïve 日本語'");
+        assert!(!t.diagnostics.has_errors());
+    }
+
+    #[test]
+    fn test_number_literals() {
+        let cases = [
+            ("1_000_000", NumberKind::Int { radix: 10 }),
+            ("3.14", NumberKind::Float),
+            ("6.02e23", NumberKind::Float),
+            ("1e-9", NumberKind::Float),
+            ("0xFF", NumberKind::Int { radix: 16 }),
+            ("0o17", NumberKind::Int { radix: 8 }),
+            ("0b1010_0101", NumberKind::Int { radix: 2 }),
+        ];
+        for (literal, kind) in cases {
+            let code = format!("float a = {}\n", literal);
+            let mut t = Tokenizer::new(file!(), line!() as usize, &code);
+            let var = Var::new(&mut t).unwrap();
+            assert_eq!(var.default_value.0, ExpressionPiece::NumberLiteral(literal, kind));
+        }
+
+        for broken in ["0b102", "12px", "1_", "0x", "2e+"] {
+            let code = format!("int a = {}\n", broken);
+            let mut t = Tokenizer::new(file!(), line!() as usize, &code);
+            assert!(Var::new(&mut t).is_err(), "{} should not lex", broken);
+            assert_eq!(t.diagnostics.error_count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_bool_null_and_char_literals() {
+        let cases = [
+            ("true", ExpressionPiece::BoolLiteral(true)),
+            ("false", ExpressionPiece::BoolLiteral(false)),
+            ("null", ExpressionPiece::NullLiteral),
+            ("'a'", ExpressionPiece::CharLiteral('a')),
+            ("'\\n'", ExpressionPiece::CharLiteral('\n')),
+            ("'ü'", ExpressionPiece::CharLiteral('ü')),
+            ("\"a\"", ExpressionPiece::StringLiteral("a".to_string())),
+            ("'ab'", ExpressionPiece::StringLiteral("ab".to_string())),
+        ];
+        for (literal, piece) in cases {
+            let code = format!("int? a = {}\n", literal);
+            let mut t = Tokenizer::new(file!(), line!() as usize, &code);
+            let var = Var::new(&mut t).unwrap();
+            assert_eq!(var.default_value.0, piece, "{}", literal);
+        }
+    }
+
     #[test]
     fn post_type_system_upgrade_var_test() {
-        let mut t = Tokenizer {
-            mutex: Mutex::new(()),
-            file_name: file!(),
-            start_line: line!() as usize,
-            code: "
+        let mut t = Tokenizer::new(
+            file!(),
+            line!() as usize,
+            "
                 const []int  a = 9
 
 
             ",
-            parse_index: 0,
-        };
+        );
 
-        assert_eq!(t.expect(TokenType::KEYWORD), "const");
-        let var = Var::new(&mut t);
+        assert_eq!(t.expect(TokenType::KEYWORD).unwrap(), "const");
+        let var = Var::new(&mut t).unwrap();
         var.display();
         assert_eq!(var.name, "a");
     }
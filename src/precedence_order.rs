@@ -1,55 +1,140 @@
+use crate::diagnostics::Span;
 use crate::libs::linkedList::*;
 use crate::parser::expression::Expression;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    // #[test]
+    use crate::parser::expression::tests::shape;
+    use crate::project_basic_utils::tokenizer::Tokenizer;
+
+    #[test]
+    fn test_every_binary_operator_has_a_level() {
+        assert_eq!(shape("a == b + 1 && c < d << 2"), "(&& (== a (+ b 1)) (< c (<< d 2)))");
+        assert_eq!(shape("a | b ^ c & d != e"), "(| a (^ b (& c (!= d e))))");
+        assert_eq!(shape("a || b && c"), "(|| a (&& b c))");
+        assert_eq!(shape("a = b += c - d - e"), "(= a (+= b (- (- c d) e)))");
+        assert_eq!(shape("x = 0..n * 2 >= 1"), "(= x (.. 0 (>= (* n 2) 1)))");
+
+        let mut t = Tokenizer::new(file!(), line!() as usize, "a => b");
+        assert!(Expression::new(&mut t, '\n', '\n').is_err());
+    }
+
+    #[test]
+    fn test_needs_parentheses() {
+        let mut t = Tokenizer::new(file!(), line!() as usize, "a - (b - c) * d");
+        let Expression(ExpressionPiece::FunctionCall(minus), _) = Expression::new(&mut t, '\n', '\n').unwrap() else {
+            panic!("expected a call");
+        };
+        let ExpressionPiece::FunctionCall(times) = &minus.params[1].0 else {
+            panic!("expected a call");
+        };
+        assert!(!needs_parentheses("-", &minus.params[0], false));
+        assert!(!needs_parentheses("-", &minus.params[1], true));
+        assert!(needs_parentheses("*", &times.params[0], false));
+        assert!(needs_parentheses("-", &times.params[0], true));
+        assert!(!needs_parentheses("-", &times.params[0], false));
+
+        let mut t = Tokenizer::new(file!(), line!() as usize, "(a = b) = c");
+        let Expression(ExpressionPiece::FunctionCall(assignment), _) = Expression::new(&mut t, '\n', '\n').unwrap() else {
+            panic!("expected a call");
+        };
+        assert!(needs_parentheses("=", &assignment.params[0], false));
+    }
 }
 
 static OPERAND_CHARS: &[char] = &['+', '-', '*', '/', '%', '=', '!', '&', '|', '(', ')'];
 
+//operators that go in front of a single value, they bind tighter than everything in OPERATOR_PRECEDENCE so -a * b is (-a) * b
+pub static PREFIX_OPERATORS: [&str; 3] = ["-", "!", "~"];
+
+//these group to the right, a = b = c is a = (b = c)
+pub static ASSIGNMENT_OPERATORS: [&str; 11] = ["=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>="];
+
 use std::sync::LazyLock;
+//same levels as c and javascript, higher binds tighter
 static OPERATOR_PRECEDENCE: LazyLock<std::collections::HashMap<String, u8>> = LazyLock::new(|| {
+    let levels: [(&[&str], u8); 12] = [
+        (&["*", "/", "%"], 12),
+        (&["+", "-"], 11),
+        (&["<<", ">>"], 10),
+        (&["<", "<=", ">", ">="], 9),
+        (&["==", "!="], 8),
+        (&["&"], 7),
+        (&["^"], 6),
+        (&["|"], 5),
+        (&["&&"], 4),
+        (&["||"], 3),
+        //so 0..n - 1 is 0..(n - 1)
+        (&[".."], 2),
+        (&ASSIGNMENT_OPERATORS, 1),
+    ];
     let mut map = std::collections::HashMap::new();
-    map.insert("*".to_string(), 3);
-    map.insert("/".to_string(), 3);
-    map.insert("%".to_string(), 3);
-    map.insert("+".to_string(), 2);
-    map.insert("-".to_string(), 2);
-    map.insert("=".to_string(), 1);
-    map.insert("!".to_string(), 1);
-    map.insert("&".to_string(), 1);
-    map.insert("-=".to_string(), 1);
-    map.insert("+=".to_string(), 1);
-    map.insert("*=".to_string(), 1);
-    map.insert("/=".to_string(), 1);
-    map.insert("%=".to_string(), 1);
+    for (operators, precedence) in levels {
+        for operator in operators {
+            map.insert(operator.to_string(), precedence);
+        }
+    }
     map
 });
 
 use std::fmt::Display;
 
+/// how tightly an operator between two values binds, higher binds tighter. None for operators the table doesnt have
+pub fn precedence(operator: &str) -> Option<u8> {
+    OPERATOR_PRECEDENCE.get(operator).copied()
+}
+
+/// whether the expression is an operator (prefix or between two values), anything that goes right up against it
+/// like a prefix operator or a member access needs it in parentheses
+pub fn is_operator_call(expression: &Expression) -> bool {
+    matches!(&expression.0, ExpressionPiece::FunctionCall(call) if !call.name.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+}
+
+/// whether an operand of a binary operator has to be put in parentheses so it comes out with the same tree it was parsed into.
+/// operators of the same precedence group to the left (other than assignments), so its only the right operand that needs them then
+pub fn needs_parentheses(operator: &str, operand: &Expression, is_right: bool) -> bool {
+    let ExpressionPiece::FunctionCall(call) = &operand.0 else {
+        return false;
+    };
+    //calls to named functions and prefix operators bind tighter than any binary operator
+    if call.params.len() != 2 || call.name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return false;
+    }
+    match (precedence(operator), precedence(&call.name)) {
+        (Some(outer), Some(inner)) => inner < outer || (inner == outer && is_right != ASSIGNMENT_OPERATORS.contains(&operator)),
+        //without a precedence to go off of they always get them
+        _ => true,
+    }
+}
+
 use crate::parser::expression::ExpressionPiece;
 use crate::parser::expression::FunctionCall;
 use crate::parser::expression::operator_to_string;
 
-fn two_down_is_greater(ll: &mut LinkedList<ExpressionPiece>, node_index: NodeIndex) -> bool {
+fn two_down_is_greater(ll: &mut LinkedList<Expression>, node_index: NodeIndex) -> bool {
     let double_next = ll.get_two_down(node_index);
     if double_next.is_none() {
         return false;
     }
-    if let ExpressionPiece::Operator(two_down_op) = ll.storage[double_next.unwrap()].value {
-        if let ExpressionPiece::Operator(this_op) = ll.storage[node_index].value {
-            let double_next_precedence = OPERATOR_PRECEDENCE.get(two_down_op).unwrap();
-            let this_onces_precedence = OPERATOR_PRECEDENCE.get(this_op).unwrap();
-            return this_onces_precedence < double_next_precedence;
+    if let ExpressionPiece::Operator(two_down_op) = ll.storage[double_next.unwrap()].value.0 {
+        if let ExpressionPiece::Operator(this_op) = ll.storage[node_index].value.0 {
+            //one the table doesnt know gets reported when its absorbed itself
+            let (Some(double_next_precedence), Some(this_onces_precedence)) = (precedence(&two_down_op), precedence(&this_op)) else {
+                return false;
+            };
+            return this_onces_precedence < double_next_precedence
+                || (this_onces_precedence == double_next_precedence && ASSIGNMENT_OPERATORS.contains(&two_down_op.as_str()));
         }
     }
     false
 }
 
-pub fn absorb_neighbors(ll: &mut LinkedList<ExpressionPiece>, node_index: NodeIndex) ->Result<(), String> {
+pub fn absorb_neighbors(ll: &mut LinkedList<Expression>, node_index: NodeIndex) ->Result<(), String> {
+    let operator = operator_to_string(&ll.storage[node_index].value.0);
+    if precedence(&operator).is_none() {
+        return Err(format!("`{}` cant be used between two values", operator));
+    }
     while two_down_is_greater(ll, node_index) {
         absorb_neighbors(ll, ll.get_two_down(node_index).unwrap())?
     }
@@ -62,23 +147,16 @@ pub fn absorb_neighbors(ll: &mut LinkedList<ExpressionPiece>, node_index: NodeIn
     if next.is_none() {
         return Err("there must be some kind of value/expression after an operator".to_string());
     }
-    ll.storage[node_index].value = ExpressionPiece::FunctionCall(FunctionCall {
-        name: operator_to_string(&ll.storage[node_index].value),
-        params: vec![
-            Expression(
-                ll.storage
-                    [prev.unwrap()] //we can unwrap because we checked that prev is not none
-                .value
-                .clone(),
-            ),
-            Expression(
-                ll.storage
-                    [next.unwrap()] //we can unwrap because we checked that prev is not none
-                .value
-                .clone(),
-            ),
-        ],
-    });
+    let left = ll.storage[prev.unwrap()].value.clone(); //we can unwrap because we checked that prev is not none
+    let right = ll.storage[next.unwrap()].value.clone(); //we can unwrap because we checked that next is not none
+    let span = Span::new(left.1.start, right.1.end);
+    ll.storage[node_index].value = Expression(
+        ExpressionPiece::FunctionCall(FunctionCall {
+            name: operator_to_string(&ll.storage[node_index].value.0),
+            params: vec![left, right],
+        }),
+        span,
+    );
     ll.remove(prev.unwrap());
     ll.remove(next.unwrap());
     Ok(())
@@ -1,36 +1,51 @@
+use crate::project_basic_utils::keyword::Keyword;
+use crate::project_basic_utils::token::TokenType;
 use crate::project_basic_utils::tokenizer::*;
 
 pub fn looks_like_type(t: &mut Tokenizer) -> bool {
-    t.eat_spaces();
-    if t.current_char() == ',' {
-        return false;
-    }
-    if t.current_char().is_alphabetic() || t.current_char() == '_' {
+    if t.peek(0).type_ == TokenType::IDENTIFIER || t.peek_keyword() == Some(Keyword::Function) {
         return true;
     }
-    if t.current_char() == '*' {
+    if t.at("*") {
         return true;
     }
-    if t.current_char() == '[' {
+    if t.at("[") {
         return true;
     }
-    if t.current_char() == '(' {
+    if t.at("(") {
         return true;
     }
     return false;
 }
 
 pub fn looks_like_expression(t: &mut Tokenizer) -> bool {
-    t.eat_spaces();
-    if t.current_char().is_alphabetic() || t.current_char() == '_' {
+    if t.peek(0).type_ == TokenType::IDENTIFIER {
         return true;
     }
     return false;
 }
 
+/// right after the [ of a literal, a : before the first , (outside of any brackets in the elements) makes it a map
+pub fn looks_like_map_literal(t: &mut Tokenizer) -> bool {
+    let mut depth = 0;
+    for token in &t.tokens[t.position..] {
+        if token.type_ != TokenType::PUNCTUATION {
+            continue;
+        }
+        match token.value {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" if depth == 0 => return false,
+            ")" | "]" | "}" => depth -= 1,
+            ":" | "," if depth == 0 => return token.value == ":",
+            _ => {}
+        }
+    }
+    return false;
+}
+
+/// keywords count too so a reserved word used as a name gets the reserved word error instead of a confusing one
 pub fn looks_like_identifier(t: &mut Tokenizer) -> bool {
-    t.eat_spaces();
-    if t.current_char().is_alphabetic() || t.current_char() == '_' {
+    if matches!(t.peek(0).type_, TokenType::IDENTIFIER | TokenType::KEYWORD) {
         return true;
     }
     return false;
@@ -0,0 +1,87 @@
+/// every word the language reserves, the tokenizer and the parsers both go off of this table.
+/// some are reserved before the parser does anything with them so adding the feature later doesnt break code that used them as names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Keyword {
+    Class,
+    Function,
+    Let,
+    Const,
+    If,
+    Else,
+    While,
+    For,
+    In,
+    Return,
+    Break,
+    Continue,
+    Enum,
+    Match,
+    True,
+    False,
+    Null,
+}
+
+impl Keyword {
+    pub const ALL: [Keyword; 17] = [
+        Keyword::Class,
+        Keyword::Function,
+        Keyword::Let,
+        Keyword::Const,
+        Keyword::If,
+        Keyword::Else,
+        Keyword::While,
+        Keyword::For,
+        Keyword::In,
+        Keyword::Return,
+        Keyword::Break,
+        Keyword::Continue,
+        Keyword::Enum,
+        Keyword::Match,
+        Keyword::True,
+        Keyword::False,
+        Keyword::Null,
+    ];
+
+    /// what can start an item outside of a function
+    pub const TOP_LEVEL: [Keyword; 5] = [Keyword::Class, Keyword::Enum, Keyword::Function, Keyword::Let, Keyword::Const];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Keyword::Class => "class",
+            Keyword::Function => "function",
+            Keyword::Let => "let",
+            Keyword::Const => "const",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::While => "while",
+            Keyword::For => "for",
+            Keyword::In => "in",
+            Keyword::Return => "return",
+            Keyword::Break => "break",
+            Keyword::Continue => "continue",
+            Keyword::Enum => "enum",
+            Keyword::Match => "match",
+            Keyword::True => "true",
+            Keyword::False => "false",
+            Keyword::Null => "null",
+        }
+    }
+
+    pub fn from_word(word: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|keyword| keyword.as_str() == word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_keyword_round_trips() {
+        for keyword in Keyword::ALL {
+            assert_eq!(Keyword::from_word(keyword.as_str()), Some(keyword));
+        }
+        assert_eq!(Keyword::from_word("fn"), None);
+        assert_eq!(Keyword::from_word("iffy"), None);
+    }
+}
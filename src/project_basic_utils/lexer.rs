@@ -0,0 +1,278 @@
+use crate::constants::*;
+use crate::diagnostics::*;
+use crate::project_basic_utils::string_literal;
+use crate::project_basic_utils::token::*;
+
+type Int = usize;
+
+/// identifiers follow the unicode XID rules (same as rust), with _ allowed at the start as well
+pub fn is_identifier_start(c: char) -> bool {
+    c == '_' || unicode_ident::is_xid_start(c)
+}
+
+pub fn is_identifier_continue(c: char) -> bool {
+    unicode_ident::is_xid_continue(c)
+}
+
+/// turns the whole file into tokens in one pass, it always ends with an EOF token.
+/// whitespace and comments dont become tokens, all thats left of them is the newline_before flag on the token after them.
+/// what doesnt lex gets reported to diagnostics and becomes an ERROR token so the parsers can stop there without reporting it again
+pub fn lex<'src>(code: &'src str, diagnostics: &mut DiagnosticSink) -> Vec<Token<'src>> {
+    let mut lexer = Lexer { code, index: 0 };
+    let mut tokens = Vec::new();
+    loop {
+        let newline_before = lexer.eat_trivia() || tokens.is_empty();
+        let start = lexer.index;
+        if !lexer.in_range() {
+            tokens.push(Token {
+                type_: TokenType::EOF,
+                value: "",
+                span: Span::new(start, start),
+                newline_before,
+            });
+            return tokens;
+        }
+        let type_ = match lexer.scan_token() {
+            Ok(type_) => type_,
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                TokenType::ERROR
+            }
+        };
+        let value = match type_ {
+            TokenType::STRING => &code[start + 1..lexer.index - 1],
+            _ => &code[start..lexer.index],
+        };
+        tokens.push(Token {
+            type_,
+            value,
+            span: Span::new(start, lexer.index),
+            newline_before,
+        });
+    }
+}
+
+struct Lexer<'src> {
+    code: &'src str,
+    index: Int,
+}
+
+impl Lexer<'_> {
+    fn in_range(&self) -> bool {
+        self.index < self.code.len()
+    }
+
+    fn current_char(&self) -> char {
+        self.code[self.index.min(self.code.len())..].chars().next().unwrap_or('\0')
+    }
+
+    fn next_char(&self) -> char {
+        let mut chars = self.code[self.index.min(self.code.len())..].chars();
+        chars.next();
+        chars.next().unwrap_or('\0')
+    }
+
+    /// steps over the current char, which can be more than one byte
+    fn advance(&mut self) {
+        self.index += self.current_char().len_utf8();
+    }
+
+    fn error(&self, start: Int, end: Int, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::error(codes::SYNTAX_ERROR, Span::new(start, end), message)
+    }
+
+    /// skips whitespace and // comments, gives back whether a line break was skipped
+    fn eat_trivia(&mut self) -> bool {
+        let mut saw_newline = false;
+        while self.in_range() {
+            if self.current_char() == '/' && self.next_char() == '/' {
+                while self.in_range() && self.current_char() != '\n' {
+                    self.advance();
+                }
+            } else if SPACE_CHARS.contains(&self.current_char()) {
+                saw_newline |= self.current_char() == '\n';
+                self.index += 1;
+            } else {
+                break;
+            }
+        }
+        saw_newline
+    }
+
+    fn scan_token(&mut self) -> Result<TokenType, Diagnostic> {
+        let start = self.index;
+        let current = self.current_char();
+        if current.is_ascii_digit() {
+            self.scan_number_literal()?;
+            return Ok(TokenType::NUMBER);
+        }
+        if is_identifier_start(current) {
+            while self.in_range() && is_identifier_continue(self.current_char()) {
+                self.advance();
+            }
+            if KEYWORDS_TRIE.is_word(&self.code[start..self.index]) {
+                return Ok(TokenType::KEYWORD);
+            }
+            return Ok(TokenType::IDENTIFIER);
+        }
+        if current == '\'' || current == '"' {
+            self.scan_string_literal(current)?;
+            return Ok(TokenType::STRING);
+        }
+        let operator_len = OPERATORS_TRIE.the_most_we_can_collect_on_word(&self.code[start..]);
+        if operator_len > 0 {
+            self.index += operator_len;
+            return Ok(TokenType::OPERATOR);
+        }
+        let punctuation_len = PUNCTUATION_TRIE.the_most_we_can_collect_on_word(&self.code[start..]);
+        if punctuation_len > 0 {
+            self.index += punctuation_len;
+            return Ok(TokenType::PUNCTUATION);
+        }
+        //brackets and the like
+        if current.is_ascii_punctuation() {
+            self.index += 1;
+            return Ok(TokenType::PUNCTUATION);
+        }
+        self.advance();
+        Err(self.error(start, self.index, format!("unexpected character `{}`", current)))
+    }
+
+    /// eats the whole literal with its quotes, escapes are checked here but turned into chars by string_literal::unescape
+    fn scan_string_literal(&mut self, quote: char) -> Result<(), Diagnostic> {
+        let token_start = self.index;
+        self.advance(); // skip the opening quote
+        let content_start = self.index;
+        while self.in_range() && self.current_char() != quote && self.current_char() != '\n' {
+            if self.current_char() == '\\' {
+                self.advance(); // an escaped quote doesnt end the string
+                if self.current_char() == '\n' {
+                    break;
+                }
+            }
+            self.advance();
+        }
+        if self.current_char() != quote {
+            //stopping at the end of the line keeps the rest of the file from being swallowed into the string
+            return Err(Diagnostic::error(
+                codes::UNTERMINATED_STRING,
+                Span::new(token_start, self.index),
+                "unterminated string literal",
+            )
+            .with_note(format!("string literals end with a matching {} on the same line, use \\n for line breaks", quote)));
+        }
+        let value = &self.code[content_start..self.index];
+        self.advance(); // skip the closing quote
+        if let Err(error) = string_literal::unescape(value) {
+            let escape_start = content_start + error.offset;
+            return Err(self.error(escape_start, escape_start + error.len, error.message));
+        }
+        Ok(())
+    }
+
+    /// 42, 1_000_000, 3.14, 6.02e23, 0xFF, 0o17 and 0b1010, the kind gets worked out from the text later by NumberKind::of
+    fn scan_number_literal(&mut self) -> Result<(), Diagnostic> {
+        let start = self.index;
+        let rest = &self.code[start..];
+        let (radix, radix_name) = match rest.get(..2) {
+            Some("0x") | Some("0X") => (16, "hexadecimal"),
+            Some("0o") | Some("0O") => (8, "octal"),
+            Some("0b") | Some("0B") => (2, "binary"),
+            _ => (10, "decimal"),
+        };
+        if radix != 10 {
+            self.index += 2;
+        }
+        let digits_start = self.index;
+        //everything that could be part of the number is taken so 0b102 or 12px get one clear error instead of being split into two tokens
+        self.eat_number_chars();
+        if radix == 10 {
+            if self.current_char() == '.' && self.next_char().is_ascii_digit() {
+                self.index += 1;
+                self.eat_number_chars();
+            }
+            if matches!(self.code[digits_start..self.index].chars().last(), Some('e' | 'E'))
+                && matches!(self.current_char(), '+' | '-')
+            {
+                //the e was already taken by eat_number_chars, this is the sign of the exponent
+                self.index += 1;
+                self.eat_number_chars();
+            }
+        }
+        let digits = &self.code[digits_start..self.index];
+
+        if digits.trim_matches('_').is_empty() {
+            return Err(self.error(start, self.index, format!("{} literal has no digits", radix_name)));
+        }
+        if digits.ends_with('_') {
+            return Err(self.error(start, self.index, "a number cant end with `_`"));
+        }
+        let exponent = if radix == 10 { digits.find(['e', 'E']) } else { None };
+        let mantissa = &digits[..exponent.unwrap_or(digits.len())];
+        if let Some(bad_index) = mantissa.find(|c: char| !(c == '_' || c == '.' || c.is_digit(radix))) {
+            let bad_char = mantissa[bad_index..].chars().next().expect("find gave back a char boundary");
+            let bad_start = digits_start + bad_index;
+            let message = if radix == 10 {
+                format!("invalid suffix `{}` on number literal", &self.code[bad_start..self.index])
+            } else {
+                format!("invalid digit `{}` in {} literal", bad_char, radix_name)
+            };
+            return Err(self.error(bad_start, self.index, message));
+        }
+        if let Some(exponent) = exponent {
+            let exponent_digits = digits[exponent + 1..].trim_start_matches(['+', '-']);
+            if exponent_digits.is_empty() || !exponent_digits.chars().all(|c| c == '_' || c.is_ascii_digit()) {
+                return Err(self.error(
+                    digits_start + exponent,
+                    self.index,
+                    "expected digits after the exponent of a float",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn eat_number_chars(&mut self) {
+        while self.in_range() && (self.current_char().is_ascii_alphanumeric() || self.current_char() == '_') {
+            self.index += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lex() {
+        let code = "let int größe = a>>2 // not a token\n  foo(\"x\\n\", 'y')\n0b2";
+        let mut diagnostics = DiagnosticSink::default();
+        let tokens = lex(code, &mut diagnostics);
+        let kinds_and_values: Vec<(TokenType, &str)> =
+            tokens.iter().map(|token| (token.type_.clone(), token.value)).collect();
+        assert_eq!(
+            kinds_and_values,
+            vec![
+                (TokenType::KEYWORD, "let"),
+                (TokenType::IDENTIFIER, "int"),
+                (TokenType::IDENTIFIER, "größe"),
+                (TokenType::OPERATOR, "="),
+                (TokenType::IDENTIFIER, "a"),
+                (TokenType::OPERATOR, ">>"),
+                (TokenType::NUMBER, "2"),
+                (TokenType::IDENTIFIER, "foo"),
+                (TokenType::PUNCTUATION, "("),
+                (TokenType::STRING, "x\\n"),
+                (TokenType::PUNCTUATION, ","),
+                (TokenType::STRING, "y"),
+                (TokenType::PUNCTUATION, ")"),
+                (TokenType::ERROR, "0b2"),
+                (TokenType::EOF, ""),
+            ]
+        );
+        assert_eq!(&code[tokens[9].span.start..tokens[9].span.end], "\"x\\n\"");
+        let on_new_line: Vec<&str> = tokens.iter().filter(|token| token.newline_before).map(|token| token.value).collect();
+        assert_eq!(on_new_line, vec!["let", "foo", "0b2"]);
+        assert_eq!(diagnostics.error_count(), 1);
+    }
+}
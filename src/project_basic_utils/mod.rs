@@ -1,2 +1,5 @@
+pub mod keyword;
+pub mod lexer;
+pub mod string_literal;
 pub mod token;
 pub mod tokenizer;
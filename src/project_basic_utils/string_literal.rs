@@ -0,0 +1,105 @@
+type Int = usize;
+
+/// what went wrong inside a string literal, offset and len are in bytes from the start of the literal's content (after the opening quote)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscapeError {
+    pub offset: Int,
+    pub len: Int,
+    pub message: String,
+}
+
+/// turns the escapes in the content of a string literal (\n \t \r \0 \\ \" \' \u{...}) into the chars they stand for
+pub fn unescape(raw: &str) -> Result<String, EscapeError> {
+    let mut res = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+    while let Some((offset, char)) = chars.next() {
+        if char != '\\' {
+            res.push(char);
+            continue;
+        }
+        let Some((_, escaped)) = chars.next() else {
+            return Err(EscapeError {
+                offset,
+                len: 1,
+                message: "a string literal cant end with a lone `\\`".to_string(),
+            });
+        };
+        res.push(match escaped {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'u' => {
+                let (unicode_char, len) = unescape_unicode(&raw[offset..]).map_err(|message| EscapeError {
+                    offset,
+                    len: escape_len(&raw[offset..]),
+                    message,
+                })?;
+                //the \ and the u were already taken off
+                for _ in 2..len {
+                    chars.next();
+                }
+                unicode_char
+            }
+            _ => {
+                return Err(EscapeError {
+                    offset,
+                    len: 1 + escaped.len_utf8(),
+                    message: format!("unknown escape sequence `\\{}`", escaped),
+                });
+            }
+        });
+    }
+    Ok(res)
+}
+
+/// escape starts at the \ of a \u{...}, gives back the char and how many chars the whole escape takes up
+fn unescape_unicode(escape: &str) -> Result<(char, Int), String> {
+    let Some(rest) = escape.strip_prefix("\\u{") else {
+        return Err("a unicode escape looks like `\\u{1F600}`".to_string());
+    };
+    let Some(hex_len) = rest.find('}') else {
+        return Err("unterminated unicode escape, expected a `}`".to_string());
+    };
+    let hex = &rest[..hex_len];
+    if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("`{}` is not 1 to 6 hex digits", hex));
+    }
+    let code_point = u32::from_str_radix(hex, 16).expect("checked that its only hex digits");
+    match char::from_u32(code_point) {
+        Some(char) => Ok((char, "\\u{}".len() + hex_len)),
+        None => Err(format!("`{}` is not a valid unicode char", hex)),
+    }
+}
+
+/// how much of a broken \u escape to underline, up to its } if it has one on the same line
+fn escape_len(escape: &str) -> Int {
+    escape
+        .find(['}', '"', '\'', '\n'])
+        .map_or(escape.len(), |index| index + usize::from(escape[index..].starts_with('}')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(r#"a\nb\tc\\d\"e\'f"#).unwrap(), "a\nb\tc\\d\"e'f");
+        assert_eq!(unescape(r"smile \u{1F600}!").unwrap(), "smile 😀!");
+        assert_eq!(unescape("größe").unwrap(), "größe");
+    }
+
+    #[test]
+    fn test_bad_escapes() {
+        let error = unescape(r"ab\q").unwrap_err();
+        assert_eq!((error.offset, error.len), (2, 2));
+        assert_eq!(error.message, "unknown escape sequence `\\q`");
+        assert!(unescape(r"\u{D800}").is_err());
+        assert!(unescape(r"\u{1234567}").is_err());
+        assert_eq!(unescape(r"x\u{12").unwrap_err().len, 5);
+    }
+}
@@ -1,6 +1,6 @@
 use core::str;
 
-type Int = usize;
+use crate::diagnostics::Span;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
@@ -11,12 +11,17 @@ pub enum TokenType {
     PUNCTUATION,
     OPERATOR,
     // DELIMITER,
+    //something the lexer already reported, the parsers bail on it without reporting again
+    ERROR,
     EOF,
 }
 
 #[derive(Debug, Clone)]
-pub struct Token {
+pub struct Token<'src> {
     pub type_: TokenType,
-    pub value: &'static str,
-    pub start_index: Int,
+    //for strings its the raw content between the quotes, the span still covers the quotes
+    pub value: &'src str,
+    pub span: Span,
+    //trivia: a line break (maybe with comments) came between this token and the one before it
+    pub newline_before: bool,
 }
@@ -1,377 +1,272 @@
-use core::panic;
-use std::process;
-use std::sync::LazyLock;
-use std::sync::Mutex;
-
-use crate::constants::*;
+use crate::diagnostics::*;
+use crate::line_index::LineIndex;
+use crate::source_db::{FileId, SourceDb};
+use crate::project_basic_utils::keyword::Keyword;
+use crate::project_basic_utils::lexer;
 use crate::project_basic_utils::token::*;
-use crate::utils::blue;
-use crate::utils::red;
+use crate::symbol::{self, Symbol};
 
 type Int = usize;
 
-pub struct Tokenizer {
-    pub mutex: Mutex<()>, /**
-        the assumption is that when you call some kind of tokenizing function you already have the tokenizer unlocked
-    */
-    pub file_name: &'static str,
-    pub start_line: Int,
-    pub code: &'static str,
-    pub parse_index: Int,
+/// a cursor over the tokens of a file, the whole file is lexed once up front so looking ahead is just indexing
+pub struct Tokenizer<'src> {
+    pub file_name: &'src str,
+    //built once when the file is loaded, everything that turns a span into a line and column goes through it
+    pub line_index: LineIndex,
+    pub code: &'src str,
+    pub tokens: Vec<Token<'src>>,
+    //index into tokens of the next token to be consumed
+    pub position: Int,
+    //where the last consumed token ended, spans of nodes end here
+    pub last_end: Int,
+    pub diagnostics: DiagnosticSink,
 }
 
-impl Tokenizer {
-    pub fn in_range(&self) -> bool {
-        return self.parse_index < self.code.len();
-    }
-
-    pub fn about_to_hit_end(&self) -> bool {
-        let mut peek_index = self.parse_index;
-        while self.in_range() && self.current_char().is_ascii_whitespace() {
-            peek_index += 1;
+impl<'src> Tokenizer<'src> {
+    pub fn new(file_name: &'src str, start_line: Int, code: &'src str) -> Self {
+        let mut diagnostics = DiagnosticSink::default();
+        let tokens = lexer::lex(code, &mut diagnostics);
+        Self {
+            file_name,
+            line_index: LineIndex::new(code, start_line),
+            code,
+            tokens,
+            position: 0,
+            last_end: 0,
+            diagnostics,
         }
-        //dbg!(peek_index);
-        //dbg!(self.parse_index);
-        return peek_index == self.code.len();
     }
 
-    pub fn current_char(&self) -> char {
-        match self.code.as_bytes().get(self.parse_index) {
-            Some(&byte) => byte as char,
-            None => '\0',
-        }
-    }
-    pub fn next_char(&self) -> char {
-        match self.code.as_bytes().get(self.parse_index+1) {
-            Some(&byte) => byte as char,
-            None => '\0',
-        }
+    pub fn for_file(db: &'src SourceDb, file: FileId) -> Self {
+        let source = db.file(file);
+        Self::new(&source.name, 1, &source.text)
     }
 
-    pub fn eat_spaces(&mut self) {
-        while self.in_range() && (self.current_char() == ' ' || self.current_char() == '\t') {
-            self.parse_index += 1;
-        }
-    }
-    pub fn eat_all_spaces(&mut self) {
-        while self.in_range() && SPACE_CHARS.contains(&self.current_char()) {
-            self.parse_index += 1;
-            if self.looks_like_at_comment() {
-                self.eat_comment();
-            }
-        }
+    /// whether theres anything left other than the EOF token
+    pub fn in_range(&self) -> bool {
+        self.peek(0).type_ != TokenType::EOF
     }
 
+    /// the token n tokens ahead without consuming anything, past the end its always the EOF token
+    pub fn peek(&self, n: Int) -> &Token<'src> {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.position + n).min(last)]
+    }
 
-    pub fn looks_like_at_comment(&self) -> bool {
-        self.in_range() && self.current_char() != '/' && self.next_char() == '/'
+    pub fn next(&mut self) -> Token<'src> {
+        let token = self.peek(0).clone();
+        if token.type_ != TokenType::EOF {
+            self.position += 1;
+            self.last_end = token.span.end;
+        }
+        token
     }
 
-    pub fn eat_comment(&mut self) {
-        while self.in_range() && self.current_char() != '\n' {
-            self.parse_index += 1;
-        } 
+    /// whether the next token is this punctuation or operator, keywords and identifiers never match
+    pub fn at(&self, symbol: &str) -> bool {
+        let token = self.peek(0);
+        matches!(token.type_, TokenType::PUNCTUATION | TokenType::OPERATOR) && token.value == symbol
     }
 
-    pub fn peek_next_word(&mut self) -> &str {
-        self.eat_all_spaces();
-        let mut peek_index = self.parse_index;
-        while self.in_range() && self.code.as_bytes()[peek_index].is_ascii_alphabetic() {
-            peek_index += 1;
+    pub fn eat(&mut self, symbol: &str) -> bool {
+        if self.at(symbol) {
+            self.next();
+            return true;
         }
-        return &self.code[self.parse_index..peek_index];
+        false
     }
 
-    pub fn next_in(&mut self, chars: &Vec<char>) -> String {
-        let start = self.parse_index;
-        while self.in_range() && chars.contains(&self.current_char()) {
-            self.parse_index += 1;
-        }
-        return self.code[start..self.parse_index].to_string();
+    /// same as eat but only if the symbol is on the same line as the token before it, for `name(` being a call
+    pub fn eat_on_same_line(&mut self, symbol: &str) -> bool {
+        !self.peek(0).newline_before && self.eat(symbol)
     }
 
-    pub fn peek_next_in(&mut self, chars: &Vec<char>) -> &str {
-        let mut peek_index = self.parse_index;
-        while self.in_range() && chars.contains(&(self.code.as_bytes()[peek_index] as char)) {
-            peek_index += 1;
+    /// eats a single char symbol even when the lexer glued it to the next one, so the >> in Map<int, Box<int>> closes both
+    pub fn eat_split(&mut self, symbol: &str) -> bool {
+        let token = &self.tokens[self.position.min(self.tokens.len() - 1)];
+        if token.type_ != TokenType::OPERATOR || token.value == symbol || !token.value.starts_with(symbol) {
+            return self.eat(symbol);
         }
-        return &self.code[self.parse_index..peek_index];
+        let token = &mut self.tokens[self.position];
+        token.value = &token.value[symbol.len()..];
+        token.span.start += symbol.len();
+        token.newline_before = false;
+        self.last_end = token.span.start;
+        true
     }
 
-    pub fn expect_char(&mut self, letter: char) {
-        self.eat_all_spaces();
-        //dbg!(self.current_char());
-        if !self.in_range() {
-            panic!("your at the end of the file in a position where you still need to parse");
+    pub fn expect_symbol(&mut self, symbol: &str) -> ParseResult<Token<'src>> {
+        if self.at(symbol) {
+            return Ok(self.next());
         }
-        if self.current_char() != letter {
-            self.user_error(self.parse_index, self.parse_index + 1);
-            panic!("expected {} but got {}", letter, self.current_char());
-        }
-        self.parse_index += 1;
+        let token = self.peek(0).clone();
+        Err(self.unexpected(&token, &format!("`{}`", symbol)))
     }
 
-    pub fn expect_char_with_backups(&mut self, letter: char, backups: &[char; 1]) {
-        //the assumption is that letter is the one that will get eaten and if so the tokenizer will consume that char, if not it will look at the backups first but not step forward
-        self.eat_all_spaces();
-        //dbg!(self.current_char());
-        if !self.in_range() {
-            panic!("your at the end of the file in a position where you still need to parse");
-        }
-        if self.current_char() == letter {
-            self.parse_index += 1;
-            return;
-        }
-        for backup in backups {
-            if self.current_char() == *backup {
-                return;
-            }
+    /// the symbol gets eaten, the backups only get looked at so whoever comes next still sees them
+    pub fn expect_symbol_with_backups(&mut self, symbol: &str, backups: &[&str]) -> ParseResult<()> {
+        if self.eat(symbol) || backups.iter().any(|backup| self.at(backup)) {
+            return Ok(());
         }
-        self.user_error(self.parse_index, self.parse_index + 1);
         let formated_backups = backups
             .iter()
-            .map(|c| format!("{}", c))
+            .map(|backup| format!("`{}`", backup))
             .collect::<Vec<String>>()
             .join(", ");
-        panic!(
-            "expected {} or any of the following: {} but got {}",
-            letter,
-            formated_backups,
-            self.current_char()
-        );
+        let token = self.peek(0).clone();
+        Err(self.unexpected(&token, &format!("`{}` or any of the following: {}", symbol, formated_backups)))
     }
 
-    pub fn optionaly_expect_char(&mut self, letter: char) -> bool {
-        self.eat_spaces();
-        if self.in_range() && self.current_char() == letter {
-            self.parse_index += 1;
-            return true;
+    /// the keyword that comes next, a word that only starts with a keyword (like iffy or if_done) is an identifier so it doesnt count
+    pub fn peek_keyword(&self) -> Option<Keyword> {
+        let token = self.peek(0);
+        if token.type_ != TokenType::KEYWORD {
+            return None;
         }
-        return false;
+        Keyword::from_word(token.value)
     }
 
-    pub fn next(&mut self) -> Token {
-        self.eat_all_spaces();
-        let token_start = self.parse_index;
-        if !self.in_range() {
-            return Token {
-                type_: TokenType::EOF,
-                value: "",
-                start_index: token_start,
-            };
-        }
-
-        if self.current_char().is_numeric() {
-            return Token {
-                type_: TokenType::NUMBER,
-                value: self.expect(TokenType::NUMBER),
-                start_index: token_start,
-            };
-        }
-        if self.current_char().is_alphabetic() {
-            return Token {
-                type_: TokenType::IDENTIFIER,
-                value: self.expect(TokenType::IDENTIFIER),
-                start_index: token_start,
-            };
+    pub fn expect(&mut self, type_: TokenType) -> ParseResult<&'src str> {
+        let token = self.peek(0).clone();
+        if token.type_ == type_ {
+            self.next();
+            return Ok(token.value);
         }
-        if self.current_char().is_ascii_whitespace() {
-            self.eat_spaces();
-            return self.next();
+        if token.value == ";" {
+            return Err(self.user_error(
+                token.span.start,
+                token.span.end,
+                "in this language we dont use semicolons (this is a modern language)",
+            ));
         }
-        if OPERATORS_TRIE.contains_letter(self.current_char()) {
-            return Token {
-                type_: TokenType::OPERATOR,
-                value: self.expect(TokenType::OPERATOR),
-                start_index: self.parse_index,
-            };
+        Err(self.unexpected(&token, &format!("{:?}", type_).to_lowercase()))
+    }
+
+    /// reports that token isnt what was expected, unless the lexer already reported it
+    pub fn unexpected(&mut self, token: &Token, expected: &str) -> ParseError {
+        match token.type_ {
+            TokenType::ERROR => ParseError,
+            TokenType::EOF => self.unexpected_end_of_file(expected),
+            _ => self.user_error(
+                token.span.start,
+                token.span.end,
+                format!("expected {} but got `{}`", expected, token.value),
+            ),
         }
+    }
 
-        //its important that this run before the .is_ascii_punctuation check bc quotes are considered punctuation and will therefore be caught by the .is_ascii_punctuation check and it will parse it incorrectly
-        if self.current_char() == '\'' {
-            self.parse_index += 1;
-            let start_index = self.parse_index;
-            while self.in_range() && self.current_char() != '\'' {
-                self.parse_index += 1;
-            }
-            let token = Token {
-                type_: TokenType::STRING,
-                value: &self.code[start_index..self.parse_index],
-                start_index: self.parse_index,
-            };
-            self.parse_index += 1; // skip the closing quote, (if not the next thing that tries to parse will end up thinking that the rest of the file is part of that string)
-            return token;
+    /// panic mode recovery inside a {} block: skips the rest of the line, and the whole block if that line opened one,
+    /// it stops before the } that closes the enclosing block so the caller still sees the end of its block
+    pub fn skip_rest_of_statement(&mut self, statement_start: Int) {
+        //the broken statement already got to the end of its line, so the next line is a fresh statement
+        if self.position > statement_start && self.peek(0).newline_before {
+            return;
         }
-        if PUNCTUATION_TRIE.contains_letter(self.current_char()) {
-            // panic!("punctuation trie contains {}", self.current_char());
-            let next_punc_len =
-                PUNCTUATION_TRIE.the_most_we_can_collect_on_word(self.peek_until_space());
-            let token = Token {
-                value: &self.code[self.parse_index..self.parse_index + next_punc_len],
-                type_: TokenType::PUNCTUATION,
-                start_index: self.parse_index,
-            };
-            //dbg!(next_punc_len);
-            self.parse_index += next_punc_len;
-            return token;
+        let mut depth = 0;
+        while self.in_range() {
+            let token = self.peek(0);
+            if depth == 0 && token.newline_before && self.position > statement_start {
+                return;
+            }
+            match (&token.type_, token.value) {
+                (TokenType::PUNCTUATION, "{") => depth += 1,
+                (TokenType::PUNCTUATION, "}") if depth == 0 => return,
+                (TokenType::PUNCTUATION, "}") => depth -= 1,
+                _ => {}
+            }
+            self.next();
         }
+    }
 
-        //its important that this run before the .is_ascii_punctuation check bc quotes are considered punctuation and will therefore be caught by the .is_ascii_punctuation check and it will parse it incorrectly
-        if self.current_char() == '"' {
-            self.parse_index += 1;
-            let start_index = self.parse_index;
-            while self.in_range() && self.current_char() != '"' {
-                self.parse_index += 1;
+    /// panic mode recovery for the top level of a file: moves to the start of the next line that starts with one of the keywords,
+    /// lines indented deeper than max_indentation are skipped so methods inside a broken class dont get mistaken for top level functions
+    pub fn skip_to_next_line_starting_with(&mut self, keywords: &[Keyword], max_indentation: Int) {
+        self.next();
+        while self.in_range() {
+            let token = self.peek(0);
+            let starts_with_keyword = self.peek_keyword().is_some_and(|keyword| keywords.contains(&keyword));
+            if token.newline_before && starts_with_keyword && self.indentation_at(token.span.start) <= max_indentation {
+                return;
             }
-            let token = Token {
-                type_: TokenType::STRING,
-                value: &self.code[start_index..self.parse_index],
-                start_index: self.parse_index,
-            };
-            self.parse_index += 1; // skip the closing quote, (if not the next thing that tries to parse will end up thinking that the rest of the file is part of that string)
-            return token;
+            self.next();
         }
-        if self.current_char().is_ascii_punctuation() {
-            assert_ne!(self.current_char(), '\'', "quotes should be handled above");
-            assert_ne!(self.current_char(), '"', "quotes should be handled above");
-            return Token {
-                type_: TokenType::PUNCTUATION,
-                value: self.expect(TokenType::PUNCTUATION),
-                start_index: self.parse_index,
-            };
-        }
-
-        panic!("not implemented");
     }
-    pub fn expect(&mut self, type_: TokenType) -> &'static str {
-        //dbg!(&self.parse_index);
-        //dbg!(&self.current_char());
-        //dbg!(&type_);
-        self.eat_all_spaces();
-        if self.current_char() == ';' {
-            self.user_error(self.parse_index, self.parse_index + 1);
-            println!(
-                "{}",
-                red(
-                    "in this language we dont use semicolons (this is a modern language)"
-                        .to_string()
+
+    /// the name of something being declared, what is "variable", "class" etc and is only there for the error.
+    /// a reserved word gets reported but the parse carries on since the code around it is still fine
+    pub fn expect_name(&mut self, what: &str) -> ParseResult<(Symbol<'src>, Span)> {
+        if let Some(keyword) = self.peek_keyword() {
+            let span = self.next().span;
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::RESERVED_WORD,
+                    span,
+                    format!("`{}` is a reserved word and cant be used as a {} name", keyword.as_str(), what),
                 )
+                .with_suggestion(format!("pick another name, like `{}_`", keyword.as_str())),
             );
-            panic!("stack trace view");
-        }
-        let start = self.parse_index;
-        match type_ {
-            TokenType::NUMBER => {
-                while self.in_range() && self.current_char().is_numeric() {
-                    self.parse_index += 1;
-                }
-            }
-            TokenType::IDENTIFIER => {
-                while self.in_range()
-                    && (self.current_char().is_alphanumeric() || self.current_char() == '_')
-                {
-                    self.parse_index += 1;
-                }
-                //dbg!(start);
-                //dbg!(self.parse_index);
-                //dbg!(&self.code[start..self.parse_index]);
-                if start == self.parse_index {
-                    let next_token = &self.next();
-                    self.user_error(
-                        next_token.start_index,
-                        next_token.start_index + next_token.value.len(),
-                    );
-                    println!("dont see a valid identifier");
-                }
-            }
-            TokenType::STRING => {
-                panic!("not implemented");
-            }
-            TokenType::PUNCTUATION => {
-                let mut peek_index = self.parse_index;
-                while self.in_range() && self.current_char().is_ascii_punctuation() {
-                    peek_index += 1;
-                }
-                let longest = OPERATORS_TRIE.greety(&self.code[start..peek_index]);
-                println!("longest: {}", longest);
-                let len = longest.len();
-                self.parse_index += len;
-            }
-            TokenType::OPERATOR => {
-                let next_operator_stream = self.peek_next_in(&OPERATORS);
-                //dbg!(&next_operator_stream);
-                let next_operator_len =
-                    OPERATORS_TRIE.the_most_we_can_collect_on_word(next_operator_stream);
-                //dbg!(next_operator_len);
-                self.parse_index += next_operator_len;
-            }
-            TokenType::KEYWORD => {
-                while self.in_range() && self.current_char().is_alphabetic() {
-                    self.parse_index += 1;
-                }
-                let word = &self.code[start..self.parse_index];
-                if !KEYWORDS_TRIE.is_word(word) {
-                    println!("{} is not a keyword", word);
-                    if !KEYWORDS_TRIE.is_word(word) {
-                        self.user_error(start, self.parse_index);
-                        // Removed to fix mutable/immutable borrow issue
-                        panic!("expected a keyword but got {}", word);
-                    }
-                }
-            } // TokenType::DELIMITER => {
-            //     while self.in_range() && self.current_char().is_alphanumeric() {
-            //         self.parse_index += 1;
-            //     }
-            // },
-            TokenType::EOF => {
-                assert!(self.parse_index == self.code.len());
-            }
+            return Ok((self.intern(keyword.as_str()), span));
         }
-        if start == self.parse_index {
-            self.user_error(self.parse_index, self.parse_index + 1);
-        } 
-        return &self.code[start..self.parse_index];
+        let (name, span) = self.expect_with_span(TokenType::IDENTIFIER)?;
+        Ok((self.intern(name), span))
     }
 
-    pub fn peek_until_space(&mut self) -> &str {
-        let mut peek_index = self.parse_index;
-        while self.in_range() && !self.current_char().is_ascii_whitespace() {
-            peek_index += 1;
-        }
-        return &self.code[self.parse_index..peek_index];
+    /// every name that goes into the syntax tree is interned through here
+    pub fn intern(&self, text: &'src str) -> Symbol<'src> {
+        symbol::intern(text)
+    }
+
+    /// same as expect but also gives back where the token was, for nodes that keep the span of their name
+    pub fn expect_with_span(&mut self, type_: TokenType) -> ParseResult<(&'src str, Span)> {
+        let span = self.peek(0).span;
+        let value = self.expect(type_)?;
+        Ok((value, span))
+    }
+
+    /// the span from start up to the end of the last token that got consumed
+    pub fn span_from(&self, start: Int) -> Span {
+        Span::new(start, self.last_end.max(start))
+    }
+
+    /// how many spaces/tabs come before the first char of the line that index is on
+    pub fn indentation_at(&self, index: Int) -> Int {
+        let line_start = self.line_index.line_span(self.code, index).start;
+        self.code[line_start..]
+            .bytes()
+            .take_while(|byte| *byte == b' ' || *byte == b'\t')
+            .count()
     }
 
     //ui methods
-    pub fn user_error(&self, start_index: Int, end_index: Int) {
-        println!(
-            "{}\x1b[31;4m{}\x1b[0m{}",
-            &self.code[..start_index],
-            &self.code[start_index..end_index],
-            &self.code[end_index..]
-        );
-        let (line, column) = self.find_line_and_column(end_index);
-        let error_location_link =
-            format!("{}:{}:{}", self.file_name, line, column);
-        println!(
-            "{} {}",
-            red("error".to_string()),
-            blue(&error_location_link)
-        );
-        std::process::exit(1);
+    /// reports an error to this file's diagnostics, the returned ParseError is what the parsers bubble up
+    pub fn user_error(&mut self, start_index: Int, end_index: Int, message: impl Into<String>) -> ParseError {
+        self.report(Diagnostic::error(codes::SYNTAX_ERROR, Span::new(start_index, end_index), message))
+    }
+
+    pub fn report(&mut self, diagnostic: Diagnostic) -> ParseError {
+        self.diagnostics.push(diagnostic);
+        ParseError
+    }
+
+    pub fn unexpected_end_of_file(&mut self, expected: &str) -> ParseError {
+        let end = self.code.len();
+        self.report(Diagnostic::error(
+            codes::UNEXPECTED_END_OF_FILE,
+            Span::new(end, end),
+            format!("unexpected end of file, expected {}", expected),
+        ))
+    }
+
+    pub fn render_diagnostics(&self) -> String {
+        self.diagnostics.render(self.file_name, self.code, &self.line_index)
+    }
+
+    pub fn render_diagnostics_json(&self) -> String {
+        self.diagnostics.render_json(self.file_name, self.code, &self.line_index)
     }
 
     //ui methods
     pub fn find_line_and_column(&self, start_index: Int) -> (Int, Int) {
-        let mut line = self.start_line;
-        let mut column = 1;
-        for i in 0..start_index {
-            if self.code.as_bytes()[i] == b'\n' {
-                line += 1;
-                column = 1;
-            } else {
-                column += 1;
-            }
-        }
-        return (line, column);
+        self.line_index.line_and_column(self.code, start_index)
     }
 }
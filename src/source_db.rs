@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::Path;
+
+/// which file in a SourceDb, cheap to copy around instead of a path or a name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub name: String,
+    pub text: String,
+}
+
+/// owns the name and text of every file being compiled. tokens and syntax trees borrow their names and literals from here,
+/// so dropping the db (or giving a file new text, which needs every borrow gone first) frees everything a compile made
+#[derive(Debug, Default)]
+pub struct SourceDb {
+    files: Vec<SourceFile>,
+}
+
+impl SourceDb {
+    pub fn add_file(&mut self, name: impl Into<String>, text: impl Into<String>) -> FileId {
+        self.files.push(SourceFile {
+            name: name.into(),
+            text: text.into(),
+        });
+        FileId(self.files.len() - 1)
+    }
+
+    pub fn load(&mut self, path: &Path) -> std::io::Result<FileId> {
+        let text = fs::read_to_string(path)?;
+        Ok(self.add_file(path.to_string_lossy(), text))
+    }
+
+    /// for when a file changed on disk or in an editor, the FileId stays the same
+    pub fn set_text(&mut self, file: FileId, text: impl Into<String>) {
+        self.files[file.0].text = text.into();
+    }
+
+    pub fn file(&self, file: FileId) -> &SourceFile {
+        &self.files[file.0]
+    }
+
+    pub fn file_ids(&self) -> impl Iterator<Item = FileId> + '_ {
+        (0..self.files.len()).map(FileId)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_basic_utils::tokenizer::Tokenizer;
+
+    #[test]
+    fn test_recompiling_after_an_edit() {
+        let mut db = SourceDb::default();
+        let first = db.add_file("a.bob", "let int a = 1\n");
+        let second = db.add_file("b.bob", "let int b = 2\n");
+        assert_eq!(db.file_ids().collect::<Vec<_>>(), vec![first, second]);
+
+        let t = Tokenizer::for_file(&db, first);
+        assert_eq!(t.file_name, "a.bob");
+        assert_eq!(t.peek(2).value, "a");
+        drop(t);
+
+        db.set_text(first, "let int renamed = 1\n");
+        let t = Tokenizer::for_file(&db, first);
+        assert_eq!(t.peek(2).value, "renamed");
+        assert_eq!(db.file(second).text, "let int b = 2\n");
+    }
+}
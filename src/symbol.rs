@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// an interned name: every use of the same name gets the same id (in every file), so comparing or hashing names is comparing numbers.
+/// it keeps the text it stands for, borrowed from the SourceDb like the rest of the syntax tree, so printing a name doesnt need the table
+#[derive(Clone, Copy)]
+pub struct Symbol<'src> {
+    id: u32,
+    text: &'src str,
+}
+
+/// names the compiler makes up itself, the table starts out with these so their ids are known up front
+pub mod sym {
+    use super::Symbol;
+
+    //the object a method was called on, fields and methods used without a prefix get reached through it
+    pub const THIS: Symbol<'static> = Symbol { id: 0, text: "this" };
+
+    pub(super) const PREDEFINED: [Symbol<'static>; 1] = [THIS];
+}
+
+impl<'src> Symbol<'src> {
+    pub fn as_str(self) -> &'src str {
+        self.text
+    }
+}
+
+impl PartialEq for Symbol<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Symbol<'_> {}
+
+impl Hash for Symbol<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for Symbol<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+//text that didnt come from the interner (keywords, builtin names, what the user typed into a test) compares by its chars
+impl PartialEq<str> for Symbol<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for Symbol<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl PartialEq<Symbol<'_>> for &str {
+    fn eq(&self, other: &Symbol<'_>) -> bool {
+        *self == other.text
+    }
+}
+
+impl Deref for Symbol<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+impl fmt::Display for Symbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+impl fmt::Debug for Symbol<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.text, f)
+    }
+}
+
+//the ids for the whole compilation, files are parsed on their own threads but the same name has to get the same id in all of them.
+//the table keeps its own copy of every name so it doesnt hold on to any one file
+static IDS: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(sym::PREDEFINED.iter().map(|symbol| (symbol.text.to_string(), symbol.id)).collect()));
+
+pub fn intern(text: &str) -> Symbol<'_> {
+    //a thread that panicked while holding the lock cant have left the map half updated, so its still good to use
+    let mut ids = IDS.lock().unwrap_or_else(PoisonError::into_inner);
+    let id = match ids.get(text) {
+        Some(id) => *id,
+        None => {
+            let id = ids.len() as u32;
+            ids.insert(text.to_string(), id);
+            id
+        }
+    };
+    Symbol { id, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let code = String::from("count size count this");
+        let symbols: Vec<Symbol> = code.split(' ').map(intern).collect();
+        assert_eq!(symbols[0], symbols[2]);
+        assert_ne!(symbols[0], symbols[1]);
+        assert_eq!(symbols[3], sym::THIS);
+        assert_eq!(symbols[1], "size");
+        assert_eq!(format!("{} {:?}", symbols[0], symbols[1]), "count \"size\"");
+        //the text is borrowed from the code, not copied out of it
+        assert!(std::ptr::eq(symbols[2].as_str(), &code[11..16]));
+    }
+
+    #[test]
+    fn test_names_match_across_files() {
+        let first_file = String::from("shared only_in_first");
+        let second_file = String::from("only_in_second shared");
+        let first: Vec<Symbol> = first_file.split(' ').map(intern).collect();
+        let second: Vec<Symbol> = second_file.split(' ').map(intern).collect();
+        assert_eq!(first[0], second[1]);
+        assert_ne!(first[1], second[0]);
+    }
+}
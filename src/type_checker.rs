@@ -0,0 +1,1878 @@
+use std::cell::RefCell;
+
+use crate::diagnostics::{codes, Diagnostic, Span};
+use crate::file::File;
+use crate::libs::edit_distance::closest_match;
+use crate::parser::code_block::{always_returns, ForHead, ValidInCodeBlock, VariantPattern};
+use crate::parser::class_parser::Class;
+use crate::parser::enum_parser::Enum;
+use crate::parser::expression::{Construction, Expression, ExpressionPiece, FunctionCall, MemberAccess, NumberKind, VariantValue};
+use crate::parser::function_parser::{Function, INFERRED_TYPE};
+use crate::parser::type_parser::Type_;
+use crate::symbol::{sym, Symbol};
+
+//types that are built out of their sub_types, they are only as valid as what they hold
+static TYPE_CONSTRUCTORS: [&str; 5] = ["array", "map", "tuple", "function", "void"];
+
+//operators that compare two values and give back a bool
+static COMPARISON_OPERATORS: [&str; 6] = ["==", "!=", "<", "<=", ">", ">="];
+
+//operators that only work on bools
+static LOGICAL_OPERATORS: [&str; 3] = ["&&", "||", "!"];
+
+//operators that work on ints and floats, + also joins strings
+static ARITHMETIC_OPERATORS: [&str; 5] = ["+", "-", "*", "/", "%"];
+
+//operators that only work on ints
+static BITWISE_OPERATORS: [&str; 6] = ["<<", ">>", "&", "|", "^", "~"];
+
+//the types a literal can have, a declared type outside of these is left alone until the checker knows more about it
+static LITERAL_TYPES: [&str; 5] = ["int", "float", "string", "bool", "char"];
+
+//a name with its type, functions dont have one yet
+type Declared<'src> = (Symbol<'src>, Option<Type_<'src>>);
+
+/// where the backends need something the syntax doesnt tell them, by the span of the expression it is about
+#[derive(Default)]
+struct Lowerings {
+    //fields and methods used without a prefix, the backends have to reach those through the object
+    member_uses: Vec<Span>,
+    //indexes into a map, javascript reads and writes those through get and set
+    map_indexes: Vec<Span>,
+}
+
+impl Lowerings {
+    fn extend(&mut self, other: Lowerings) {
+        self.member_uses.extend(other.member_uses);
+        self.map_indexes.extend(other.map_indexes);
+    }
+}
+
+/// the names that can be used at some point in a function body, a new level is pushed for every {} scope
+struct Scope<'src> {
+    levels: Vec<Vec<Declared<'src>>>,
+    //the level with the fields and methods of the class when a method is being checked
+    member_level: Option<usize>,
+    //what the expressions checked in this scope need lowered for the backends
+    lowerings: RefCell<Lowerings>,
+}
+
+impl<'src> Scope<'src> {
+    fn new(names: Vec<Declared<'src>>) -> Self {
+        Self {
+            levels: vec![names],
+            member_level: None,
+            lowerings: RefCell::default(),
+        }
+    }
+
+    /// a copy for the body of an anonymous function, it can use everything around it but what it declares stays inside of it
+    fn nested(&self) -> Self {
+        Self {
+            levels: self.levels.clone(),
+            member_level: self.member_level,
+            lowerings: RefCell::default(),
+        }
+    }
+
+    /// remembers the span if the innermost declaration of name is a field or method of the class
+    fn note_member_use(&self, name: Symbol, span: Span) {
+        let level = self.levels.iter().rposition(|level| level.iter().any(|(declared, _)| *declared == name));
+        if level.is_some() && level == self.member_level {
+            self.lowerings.borrow_mut().member_uses.push(span);
+        }
+    }
+
+    fn declare(&mut self, name: Symbol<'src>, type_: Option<Type_<'src>>) {
+        self.levels.last_mut().expect("there is always a global level").push((name, type_));
+    }
+
+    fn contains(&self, name: Symbol) -> bool {
+        self.names().any(|declared| declared == name)
+    }
+
+    /// the type of the innermost declaration of name
+    fn type_of(&self, name: Symbol) -> Option<&Type_<'src>> {
+        self.levels
+            .iter()
+            .rev()
+            .flat_map(|level| level.iter().rev())
+            .find(|(declared, _)| *declared == name)
+            .and_then(|(_, type_)| type_.as_ref())
+    }
+
+    fn names(&self) -> impl Iterator<Item = Symbol<'src>> + '_ {
+        //innermost first so that when two names are as close the local one is suggested
+        self.levels.iter().rev().flat_map(|level| level.iter().rev().map(|(name, _)| *name))
+    }
+}
+
+/// a match whose arms are being checked, the enum is None when the checker doesnt know what is matched
+struct OpenMatch<'a, 'src> {
+    matched_enum: Option<&'a Enum<'src>>,
+    covered: Vec<Symbol<'src>>,
+    has_wildcard: bool,
+    subject_span: Span,
+}
+
+impl<'src> File<'src> {
+    pub fn type_check(&mut self) {
+        self.type_check_vars();
+        self.type_check_type_names();
+        self.type_check_classes();
+        self.type_check_enums();
+        self.type_check_functions();
+        self.type_check_default_values();
+        let lowerings = self.resolve_names();
+        self.lower_for_backends(&lowerings);
+    }
+
+    fn type_check_vars(&mut self) {
+        let mut diagnostics = vec![];
+        for var in &self.variables {
+            if let Some(unknown) = self.find_unknown_type(&var.type_) {
+                diagnostics.push(self.unknown_type_error(
+                    unknown,
+                    format!(
+                        "Variable {} of type {} is not allowed",
+                        var.name,
+                        var.type_.to_plain_string()
+                    ),
+                ));
+            }
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    /// classes and enums share one namespace, the second one with a name gets the error
+    fn type_check_type_names(&mut self) {
+        let mut diagnostics = vec![];
+        let classes = self.classes.iter().map(|_class| (_class.name, _class.name_span));
+        let enums = self.enums.iter().map(|_enum| (_enum.name, _enum.name_span));
+        let mut defined: Vec<(Symbol, Span)> = classes.chain(enums).collect();
+        defined.sort_by_key(|(_, name_span)| name_span.start);
+        for (index, (name, name_span)) in defined.iter().enumerate() {
+            if defined[..index].iter().any(|(earlier, _)| earlier == name) {
+                diagnostics.push(
+                    Diagnostic::error(codes::DUPLICATE_DEFINITION, *name_span, format!("the type `{}` is defined more than once", name))
+                        .with_note("class and enum names have to be unique within a file"),
+                );
+            }
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    fn type_check_classes(&mut self) {
+        let mut diagnostics = vec![];
+        for _class in &self.classes {
+            for field in &_class.fields {
+                if let Some(unknown) = self.find_unknown_type(&field.type_) {
+                    diagnostics.push(self.unknown_type_error(
+                        unknown,
+                        format!(
+                            "type {} (used as field {} of class {}) is unknown to the compiler",
+                            field.type_.to_plain_string(),
+                            field.name,
+                            _class.name
+                        ),
+                    ));
+                }
+            }
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    fn type_check_enums(&mut self) {
+        let mut diagnostics = vec![];
+        for _enum in &self.enums {
+            for variant in &_enum.variants {
+                for (name, type_) in &variant.payload {
+                    if let Some(unknown) = self.find_unknown_type(type_) {
+                        diagnostics.push(self.unknown_type_error(
+                            unknown,
+                            format!(
+                                "type {} (used as {} of variant {}.{}) is unknown to the compiler",
+                                type_.to_plain_string(),
+                                name,
+                                _enum.name,
+                                variant.name
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    fn type_check_functions(&mut self) {
+        let mut diagnostics = vec![];
+        let methods = self.classes.iter().flat_map(|_class| _class.methods.iter());
+        for function in self.functions.iter().chain(methods) {
+            for param in &function.params {
+                if let Some(unknown) = self.find_unknown_type(&param.type_) {
+                    diagnostics.push(self.unknown_type_error(
+                        unknown,
+                        format!(
+                            "type {} (used as param {} of function {}) is unknown to the compiler",
+                            param.type_.to_plain_string(),
+                            param.name,
+                            function.name
+                        ),
+                    ));
+                }
+            }
+            if let Some(unknown) = self.find_unknown_type(&function.return_type) {
+                diagnostics.push(self.unknown_type_error(
+                    unknown,
+                    format!(
+                        "type {} (used as return type of function {}) is unknown for the compiler",
+                        function.return_type.to_plain_string(),
+                        function.name
+                    ),
+                ));
+            }
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    /// for now only literals have a type the checker knows, so this catches things like `let int a = 3.14`
+    fn type_check_default_values(&mut self) {
+        let mut diagnostics = vec![];
+        let methods = self.classes.iter().flat_map(|_class| _class.methods.iter());
+        let functions = self.functions.iter().chain(methods);
+        let locals = functions.clone().flat_map(|function| {
+            function.body.iter().filter_map(|statement| match statement {
+                ValidInCodeBlock::Var(var) => Some(var),
+                _ => None,
+            })
+        });
+        for var in self.variables.iter().chain(locals) {
+            diagnostics.extend(check_literal_type(&var.type_, &var.default_value, &var.name));
+        }
+        for field in self.classes.iter().flat_map(|_class| _class.fields.iter()) {
+            diagnostics.extend(check_literal_type(&field.type_, &field.default_value, &field.name));
+        }
+        for param in functions.flat_map(|function| function.params.iter()) {
+            diagnostics.extend(check_literal_type(&param.type_, &param.default_value, &param.name));
+        }
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+    }
+
+    /// the first part of the type (the type itself or one of its sub types) that the compiler doesnt know about
+    fn find_unknown_type<'a>(&self, type_: &'a Type_<'src>) -> Option<&'a Type_<'src>> {
+        if self.builtins.iter().any(|builtin| builtin.same_type(type_)) {
+            return None;
+        }
+        if !TYPE_CONSTRUCTORS.contains(&type_.name) && !self.known_type_names().any(|name| name == type_.name) {
+            return Some(type_);
+        }
+        type_
+            .sub_types
+            .iter()
+            .find_map(|sub_type| self.find_unknown_type(sub_type))
+    }
+
+    fn known_type_names(&self) -> impl Iterator<Item = &'src str> + '_ {
+        self.classes
+            .iter()
+            .map(|_class| _class.name.as_str())
+            .chain(self.enums.iter().map(|_enum| _enum.name.as_str()))
+            .chain(self.builtins.iter().map(|builtin| builtin.name))
+    }
+
+    fn unknown_type_error(&self, unknown: &Type_, message: String) -> Diagnostic {
+        let diagnostic = Diagnostic::error(codes::UNKNOWN_TYPE, unknown.span, message);
+        match closest_match(unknown.name, self.known_type_names()) {
+            Some(suggestion) => diagnostic.with_suggestion(format!(
+                "there is no type {}, did you mean {}",
+                unknown.name, suggestion
+            )),
+            None => diagnostic,
+        }
+    }
+
+    /// checks that every variable and function that gets used has been declared,
+    /// in the global scope order doesnt matter but in a function body a local has to be declared before its used.
+    /// function bodies also get their returns checked against the return type and their break and continue against the loops around them.
+    /// gives back where methods use a field or method of their class without a prefix and where maps get indexed
+    fn resolve_names(&mut self) -> Lowerings {
+        let mut diagnostics = vec![];
+        let global_names = self.global_names();
+
+        let mut lowerings = Lowerings::default();
+        let mut global_scope = Scope::new(global_names.clone());
+        for var in &self.variables {
+            self.check_expression(&var.default_value, &global_scope, &mut diagnostics);
+        }
+        for function in &self.functions {
+            self.resolve_names_in_function(function, &mut global_scope, &mut diagnostics);
+        }
+        for _class in &self.classes {
+            for field in &_class.fields {
+                self.check_expression(&field.default_value, &global_scope, &mut diagnostics);
+            }
+            //methods can use the fields and other methods of their class without a prefix
+            let mut class_scope = Scope::new(global_names.clone());
+            class_scope.levels.push(vec![]);
+            class_scope.member_level = Some(1);
+            for field in &_class.fields {
+                class_scope.declare(field.name, Some(field.type_.clone()));
+            }
+            for method in &_class.methods {
+                class_scope.declare(method.name, None);
+            }
+            for method in &_class.methods {
+                self.resolve_names_in_function(method, &mut class_scope, &mut diagnostics);
+            }
+            lowerings.extend(class_scope.lowerings.into_inner());
+        }
+        lowerings.extend(global_scope.lowerings.into_inner());
+        self.tokenizer.diagnostics.diagnostics.extend(diagnostics);
+        lowerings
+    }
+
+    fn global_names(&self) -> Vec<Declared<'src>> {
+        self.variables
+            .iter()
+            .map(|var| (var.name, Some(var.type_.clone())))
+            .chain(self.functions.iter().map(|function| (function.name, None)))
+            .collect()
+    }
+
+    fn resolve_names_in_function(&self, function: &Function<'src>, scope: &mut Scope<'src>, diagnostics: &mut Vec<Diagnostic>) {
+        scope.levels.push(vec![]);
+        //one entry for every open block, true for the loops
+        let mut open_blocks = vec![];
+        let mut open_matches: Vec<OpenMatch> = vec![];
+        for param in &function.params {
+            self.check_expression(&param.default_value, scope, diagnostics);
+            scope.declare(param.name, Some(param.type_.clone()));
+        }
+        for (index, statement) in function.body.iter().enumerate() {
+            match statement {
+                ValidInCodeBlock::Var(var) => {
+                    self.check_expression(&var.default_value, scope, diagnostics);
+                    if let Some(unknown) = self.find_unknown_type(&var.type_) {
+                        diagnostics.push(self.unknown_type_error(
+                            unknown,
+                            format!("Variable {} of type {} is not allowed", var.name, var.type_.to_plain_string()),
+                        ));
+                    } else if literal_type(&var.default_value).is_none() {
+                        //literals were checked with the rest of the default values
+                        let found = self.type_of_value(&var.default_value, scope);
+                        diagnostics.extend(check_declared_type(&var.type_, &var.default_value, found, &var.name));
+                    }
+                    scope.declare(var.name, Some(var.type_.clone()));
+                }
+                ValidInCodeBlock::Expression(expression) => {
+                    self.check_expression(expression, scope, diagnostics);
+                    let is_condition = index > 0
+                        && matches!(
+                            function.body[index - 1],
+                            ValidInCodeBlock::WhileStartMarker | ValidInCodeBlock::IfStartMarker | ValidInCodeBlock::ElseIfStartMarker
+                        );
+                    if is_condition {
+                        diagnostics.extend(check_condition(expression, self.value_type(expression, scope)));
+                    }
+                }
+                //the parser puts calls in as expressions, this only has its params to check since theres no span for the name
+                ValidInCodeBlock::FunctionCall(function_call) => {
+                    for param in &function_call.params {
+                        self.check_expression(param, scope, diagnostics);
+                    }
+                }
+                //the condition that comes right after the marker cant declare anything so it can live in the new scope
+                ValidInCodeBlock::WhileStartMarker
+                | ValidInCodeBlock::IfStartMarker
+                | ValidInCodeBlock::ElseIfStartMarker
+                | ValidInCodeBlock::ElseStartMarker => {
+                    scope.levels.push(vec![]);
+                    open_blocks.push(matches!(statement, ValidInCodeBlock::WhileStartMarker));
+                }
+                ValidInCodeBlock::ForStartMarker(head) => {
+                    open_blocks.push(true);
+                    match &head.iterable.0 {
+                        //this is the one place a range can be, so only its ends go through the normal checks
+                        ExpressionPiece::FunctionCall(range) if range.name == ".." => {
+                            for end in &range.params {
+                                self.check_expression(end, scope, diagnostics);
+                            }
+                        }
+                        _ => self.check_expression(&head.iterable, scope, diagnostics),
+                    }
+                    let iterable_type = self.type_of_place(&head.iterable, scope);
+                    let loop_types = loop_variable_types(head, iterable_type).unwrap_or_else(|diagnostic| {
+                        diagnostics.push(diagnostic);
+                        vec![None; head.names.len()]
+                    });
+                    scope.levels.push(vec![]);
+                    for ((name, _), type_) in head.names.iter().zip(loop_types) {
+                        scope.declare(*name, type_);
+                    }
+                }
+                ValidInCodeBlock::MatchStartMarker(subject) => {
+                    self.check_expression(subject, scope, diagnostics);
+                    let subject_type = self.type_of_place(subject, scope);
+                    let matched_enum = subject_type.as_ref().and_then(|type_| self.enum_named(type_.name));
+                    if let (Some(type_), None) = (&subject_type, matched_enum) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                codes::MISMATCHED_TYPES,
+                                subject.1,
+                                format!("`{}` cant be matched", type_.to_plain_string()),
+                            )
+                            .with_note("only enums can be matched"),
+                        );
+                    }
+                    open_matches.push(OpenMatch {
+                        matched_enum,
+                        covered: vec![],
+                        has_wildcard: false,
+                        subject_span: subject.1,
+                    });
+                    scope.levels.push(vec![]);
+                    open_blocks.push(false);
+                }
+                ValidInCodeBlock::MatchArmStartMarker(pattern, arm_span) => {
+                    scope.levels.push(vec![]);
+                    open_blocks.push(false);
+                    let open_match = open_matches.last_mut().expect("the parser only puts arms inside of a match");
+                    diagnostics.extend(unreachable_arm_warning(open_match, pattern.as_ref(), *arm_span));
+                    let Some(pattern) = pattern else {
+                        open_match.has_wildcard = true;
+                        continue;
+                    };
+                    //an arm thats wrong still counts as covering its variant so the match isnt reported as well
+                    open_match.covered.push(pattern.variant);
+                    let payload_types = self.check_pattern(pattern, open_match.matched_enum).unwrap_or_else(|diagnostic| {
+                        diagnostics.push(diagnostic);
+                        vec![None; pattern.bindings.len()]
+                    });
+                    for ((name, _), type_) in pattern.bindings.iter().zip(payload_types) {
+                        scope.declare(*name, type_);
+                    }
+                }
+                ValidInCodeBlock::Return(value, span) => {
+                    if let Some(value) = value {
+                        self.check_expression(value, scope, diagnostics);
+                    }
+                    let found = value.as_ref().and_then(|value| self.type_of_value(value, scope));
+                    diagnostics.extend(check_return(function, value.as_ref(), *span, found));
+                }
+                ValidInCodeBlock::Break(span) | ValidInCodeBlock::Continue(span) => {
+                    if !open_blocks.contains(&true) {
+                        let keyword = if matches!(statement, ValidInCodeBlock::Break(_)) { "break" } else { "continue" };
+                        diagnostics.push(
+                            Diagnostic::error(codes::OUTSIDE_OF_LOOP, *span, format!("`{}` outside of a loop", keyword))
+                                .with_note(format!("`{}` can only be used inside a while or a for", keyword)),
+                        );
+                    }
+                }
+                ValidInCodeBlock::JumpIndex(start) => {
+                    scope.levels.pop();
+                    open_blocks.pop();
+                    if let ValidInCodeBlock::MatchStartMarker(_) = function.body[*start] {
+                        let open_match = open_matches.pop().expect("every match was opened before it is closed");
+                        diagnostics.extend(check_exhaustive(open_match));
+                    }
+                }
+                ValidInCodeBlock::HeadEndAndBodyStartMarker => {}
+            }
+        }
+        scope.levels.pop();
+        diagnostics.extend(check_every_path_returns(function));
+    }
+
+    /// resolves the names used in the expression and checks the operators in it have operands they work on
+    fn check_expression(&self, expression: &Expression<'src>, scope: &Scope<'src>, diagnostics: &mut Vec<Diagnostic>) {
+        match &expression.0 {
+            ExpressionPiece::Variable(name) => {
+                if !scope.contains(*name) {
+                    let diagnostic = Diagnostic::error(
+                        codes::UNKNOWN_NAME,
+                        expression.1,
+                        format!("cannot find `{}` in this scope", name),
+                    );
+                    diagnostics.push(match closest_match(name, scope.names().map(Symbol::as_str)) {
+                        Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+                        None => diagnostic,
+                    });
+                }
+                scope.note_member_use(*name, expression.1);
+            }
+            ExpressionPiece::FunctionCall(function_call) => {
+                //operators get turned into function calls named after the operator, those are always there
+                let is_named_function = function_call
+                    .name
+                    .starts_with(|c: char| c.is_alphabetic() || c == '_');
+                if is_named_function && !scope.contains(function_call.name) {
+                    let diagnostic = Diagnostic::error(
+                        codes::UNKNOWN_FUNCTION,
+                        Span::new(expression.1.start, expression.1.start + function_call.name.len()),
+                        format!("cannot find function `{}` in this scope", function_call.name),
+                    );
+                    diagnostics.push(match closest_match(&function_call.name, scope.names().map(Symbol::as_str)) {
+                        Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+                        None => diagnostic,
+                    });
+                }
+                if function_call.name == ".." {
+                    diagnostics.push(
+                        Diagnostic::error(codes::RANGE_OUTSIDE_FOR, expression.1, "a range can only be looped over")
+                            .with_note("ranges dont have a type of their own, they can only come right after the `in` of a for"),
+                    );
+                } else if is_named_function {
+                    scope.note_member_use(function_call.name, expression.1);
+                } else {
+                    let operand_types: Vec<_> = function_call.params.iter().map(|param| self.value_type(param, scope)).collect();
+                    diagnostics.extend(check_operator(function_call, expression.1, &operand_types));
+                }
+                for param in &function_call.params {
+                    self.check_expression(param, scope, diagnostics);
+                }
+            }
+            ExpressionPiece::MemberAccess(access) => {
+                for arg in access.args.iter().flatten() {
+                    self.check_expression(arg, scope, diagnostics);
+                }
+                match self.enum_of_variant(access, scope) {
+                    Some(_enum) => diagnostics.extend(self.check_variant_value(_enum, access, expression.1, scope)),
+                    None => {
+                        self.check_expression(&access.object, scope, diagnostics);
+                        diagnostics.extend(self.check_member(access, scope));
+                    }
+                }
+            }
+            ExpressionPiece::VariantValue(value) => {
+                for (_, value) in &value.payload {
+                    self.check_expression(value, scope, diagnostics);
+                }
+            }
+            ExpressionPiece::Index(object, index) | ExpressionPiece::MapIndex(object, index) => {
+                self.check_expression(object, scope, diagnostics);
+                self.check_expression(index, scope, diagnostics);
+                diagnostics.extend(self.check_index(object, index, scope));
+                if self.type_of_place(object, scope).is_some_and(|type_| type_.name == "map") {
+                    scope.lowerings.borrow_mut().map_indexes.push(expression.1);
+                }
+            }
+            ExpressionPiece::ArrayLiteral(elements) => {
+                for element in elements {
+                    self.check_expression(element, scope, diagnostics);
+                }
+            }
+            ExpressionPiece::MapLiteral(entries) => {
+                for (key, value) in entries {
+                    self.check_expression(key, scope, diagnostics);
+                    self.check_expression(value, scope, diagnostics);
+                }
+            }
+            ExpressionPiece::Construction(construction) => {
+                for (_, _, value) in &construction.fields {
+                    self.check_expression(value, scope, diagnostics);
+                }
+                diagnostics.extend(self.check_construction(construction, expression.1, scope));
+            }
+            ExpressionPiece::Lambda(function) => {
+                let written_types = function.params.iter().map(|param| &param.type_).chain([&function.return_type]);
+                for type_ in written_types.filter(|type_| type_.name != INFERRED_TYPE) {
+                    if let Some(unknown) = self.find_unknown_type(type_) {
+                        diagnostics.push(self.unknown_type_error(
+                            unknown,
+                            format!("type {} (used in an anonymous function) is unknown to the compiler", type_.to_plain_string()),
+                        ));
+                    }
+                }
+                let locals = function.body.iter().filter_map(|statement| match statement {
+                    ValidInCodeBlock::Var(var) => Some((&var.type_, &var.default_value, var.name)),
+                    _ => None,
+                });
+                let params = function.params.iter().map(|param| (&param.type_, &param.default_value, param.name));
+                for (type_, value, name) in params.chain(locals) {
+                    diagnostics.extend(check_literal_type(type_, value, &name));
+                }
+                let mut function_scope = scope.nested();
+                self.resolve_names_in_function(function, &mut function_scope, diagnostics);
+                scope.lowerings.borrow_mut().extend(function_scope.lowerings.into_inner());
+            }
+            ExpressionPiece::StringLiteral(_)
+            | ExpressionPiece::NumberLiteral(..)
+            | ExpressionPiece::BoolLiteral(_)
+            | ExpressionPiece::CharLiteral(_)
+            | ExpressionPiece::NullLiteral
+            | ExpressionPiece::Operator(_)
+            | ExpressionPiece::Placeholder(_) => {}
+        }
+    }
+
+    /// the type of a variable or of a member or element of one, None when the checker doesnt know it
+    fn type_of_place(&self, expression: &Expression, scope: &Scope<'src>) -> Option<Type_<'src>> {
+        match &expression.0 {
+            ExpressionPiece::Variable(name) => scope.type_of(*name).cloned(),
+            ExpressionPiece::MemberAccess(access) => {
+                if let Some(_enum) = self.enum_of_variant(access, scope) {
+                    return Some(Type_ {
+                        name: _enum.name.as_str(),
+                        span: access.object.1,
+                        ..Default::default()
+                    });
+                }
+                let class = self.class_named(self.type_of_place(&access.object, scope)?.name)?;
+                match access.args {
+                    None => class.fields.iter().find(|field| field.name == access.name).map(|field| field.type_.clone()),
+                    Some(_) => class.methods.iter().find(|method| method.name == access.name).map(|method| method.return_type.clone()),
+                }
+            }
+            ExpressionPiece::Index(object, _) | ExpressionPiece::MapIndex(object, _) => {
+                let object_type = self.type_of_place(object, scope)?;
+                match object_type.name {
+                    "array" => object_type.sub_types.first().cloned(),
+                    "map" => object_type.sub_types.get(1).cloned(),
+                    "string" => Some(simple_type("char")),
+                    _ => None,
+                }
+            }
+            ExpressionPiece::Construction(construction) => Some(Type_ {
+                name: self.class_named(&construction.class_name)?.name.as_str(),
+                span: construction.class_span,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    /// the name of the type of an expression, the variables, members and elements in it get theirs from the scope
+    fn value_type(&self, expression: &Expression, scope: &Scope<'src>) -> Option<&'src str> {
+        expression_type(expression, &|place| self.type_of_place(place, scope).map(|type_| type_.name))
+    }
+
+    /// the whole type when the expression is a place, otherwise just the name value_type works out
+    fn type_of_value(&self, expression: &Expression, scope: &Scope<'src>) -> Option<Type_<'src>> {
+        self.type_of_place(expression, scope).or_else(|| self.value_type(expression, scope).map(simple_type))
+    }
+
+    /// arrays and strings are indexed with an int and maps with their key type, nothing else can be indexed
+    fn check_index(&self, object: &Expression, index: &Expression, scope: &Scope<'src>) -> Option<Diagnostic> {
+        let object_type = self.type_of_place(object, scope)?;
+        let key_type = match object_type.name {
+            "array" | "string" => simple_type("int"),
+            "map" => object_type.sub_types[0].clone(),
+            _ => {
+                return Some(
+                    Diagnostic::error(
+                        codes::NOT_INDEXABLE,
+                        object.1,
+                        format!("`{}` cant be indexed", object_type.to_plain_string()),
+                    )
+                    .with_note("only arrays, maps and strings can be indexed"),
+                );
+            }
+        };
+        literal_mismatch(&key_type, index).map(|diagnostic| {
+            diagnostic.with_note(format!(
+                "`{}` is indexed with `{}`",
+                object_type.to_plain_string(),
+                key_type.to_plain_string()
+            ))
+        })
+    }
+
+    /// the fields given have to be fields of the class with values that fit them, and every field without a default value has to be given
+    fn check_construction(&self, construction: &Construction, span: Span, scope: &Scope<'src>) -> Vec<Diagnostic> {
+        let Some(class) = self.class_named(&construction.class_name) else {
+            let diagnostic = Diagnostic::error(
+                codes::UNKNOWN_TYPE,
+                construction.class_span,
+                format!("cannot find class `{}`", construction.class_name),
+            );
+            return vec![match closest_match(&construction.class_name, self.classes.iter().map(|_class| _class.name.as_str())) {
+                Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+                None => diagnostic,
+            }];
+        };
+        let mut diagnostics = vec![];
+        for (index, (name, name_span, value)) in construction.fields.iter().enumerate() {
+            if construction.fields[..index].iter().any(|(earlier, _, _)| earlier == name) {
+                diagnostics.push(Diagnostic::error(
+                    codes::DUPLICATE_FIELD,
+                    *name_span,
+                    format!("field `{}` is given more than once", name),
+                ));
+                continue;
+            }
+            let Some(field) = class.fields.iter().find(|field| field.name == *name) else {
+                let diagnostic = Diagnostic::error(
+                    codes::UNKNOWN_MEMBER,
+                    *name_span,
+                    format!("no field `{}` on `{}`", name, class.name),
+                );
+                diagnostics.push(match closest_match(name, class.fields.iter().map(|field| field.name.as_str())) {
+                    Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+                    None => diagnostic,
+                });
+                continue;
+            };
+            let declared_as = format!("`{}.{}` is declared as `{}`", class.name, field.name, field.type_.to_plain_string());
+            if let Some(diagnostic) = literal_mismatch(&field.type_, value) {
+                diagnostics.push(diagnostic.with_note(declared_as));
+                continue;
+            }
+            if let Some(found) = self.type_of_place(value, scope).filter(|found| !fits(&field.type_, found)) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        codes::MISMATCHED_TYPES,
+                        value.1,
+                        format!(
+                            "mismatched types: expected `{}`, found `{}`",
+                            field.type_.to_plain_string(),
+                            found.to_plain_string()
+                        ),
+                    )
+                    .with_note(declared_as),
+                );
+            }
+        }
+        let missing: Vec<String> = class
+            .fields
+            .iter()
+            .filter(|field| field.default_value.0 == ExpressionPiece::Placeholder(false))
+            .filter(|field| !construction.fields.iter().any(|(name, _, _)| *name == field.name))
+            .map(|field| format!("`{}`", field.name))
+            .collect();
+        if !missing.is_empty() {
+            let plural = if missing.len() == 1 { "" } else { "s" };
+            diagnostics.push(
+                Diagnostic::error(
+                    codes::MISSING_FIELD,
+                    span,
+                    format!("missing field{} {} in `{}`", plural, missing.join(", "), class.name),
+                )
+                .with_note("only fields with a default value can be left out"),
+            );
+        }
+        diagnostics
+    }
+
+    /// after checking, the backends get what the checker worked out: constructions get every field of their class,
+    /// variants of enums become VariantValues, match arms learn the names of the payload they bind
+    /// fields and methods used without a prefix become accesses on this and indexes into maps become MapIndexes
+    fn lower_for_backends(&mut self, lowerings: &Lowerings) {
+        let class_fields: Vec<(Symbol<'src>, Vec<(Symbol<'src>, Expression<'src>)>)> = self
+            .classes
+            .iter()
+            .map(|_class| {
+                let fields = _class.fields.iter().map(|field| (field.name, field.default_value.clone()));
+                (_class.name, fields.collect())
+            })
+            .collect();
+        let enums = self.enums.clone();
+        let mut lower = |expression: &mut Expression<'src>| match &mut expression.0 {
+            ExpressionPiece::Variable(name) if lowerings.member_uses.contains(&expression.1) => {
+                expression.0 = member_of_this(*name, expression.1, None);
+            }
+            ExpressionPiece::FunctionCall(call) if lowerings.member_uses.contains(&expression.1) => {
+                let args = std::mem::take(&mut call.params);
+                expression.0 = member_of_this(call.name, expression.1, Some(args));
+            }
+            ExpressionPiece::Index(..) if lowerings.map_indexes.contains(&expression.1) => {
+                if let ExpressionPiece::Index(map, key) = std::mem::replace(&mut expression.0, ExpressionPiece::NullLiteral) {
+                    expression.0 = ExpressionPiece::MapIndex(map, key);
+                }
+            }
+            ExpressionPiece::Construction(construction) => fill_in_omitted_fields(construction, &class_fields),
+            ExpressionPiece::MemberAccess(access) => {
+                if let Some(value) = variant_value(access, &enums) {
+                    expression.0 = ExpressionPiece::VariantValue(value);
+                }
+            }
+            ExpressionPiece::Lambda(function) => name_bound_payloads(&mut function.body, &enums),
+            _ => {}
+        };
+
+        let mut expressions: Vec<&mut Expression<'src>> = self.variables.iter_mut().map(|var| &mut var.default_value).collect();
+        let mut functions: Vec<&mut Function<'src>> = self.functions.iter_mut().collect();
+        for _class in &mut self.classes {
+            expressions.extend(_class.fields.iter_mut().map(|field| &mut field.default_value));
+            functions.extend(_class.methods.iter_mut());
+        }
+        for function in functions {
+            name_bound_payloads(&mut function.body, &enums);
+            expressions.extend(function.params.iter_mut().map(|param| &mut param.default_value));
+            expressions.extend(function.body.iter_mut().flat_map(|statement| statement.expressions_mut()));
+        }
+        for expression in expressions {
+            expression.walk_mut(&mut lower);
+        }
+    }
+
+    fn enum_named(&self, name: &str) -> Option<&Enum<'src>> {
+        self.enums.iter().find(|_enum| _enum.name == name)
+    }
+
+    /// the enum when the access is `Enum.Variant`, a local with the name of the enum hides it
+    fn enum_of_variant(&self, access: &MemberAccess, scope: &Scope<'src>) -> Option<&Enum<'src>> {
+        match access.object.0 {
+            ExpressionPiece::Variable(name) if !scope.contains(name) => self.enum_named(&name),
+            _ => None,
+        }
+    }
+
+    /// the variant has to be one of the enum and get a value for everything in its payload
+    fn check_variant_value(&self, _enum: &Enum, access: &MemberAccess, span: Span, scope: &Scope<'src>) -> Vec<Diagnostic> {
+        let variant = match _enum.variant(access.name) {
+            Some(variant) => variant,
+            None => return vec![unknown_variant_error(_enum, &access.name, access.name_span)],
+        };
+        let values = access.args.as_deref().unwrap_or_default();
+        if values.len() != variant.payload.len() {
+            return vec![Diagnostic::error(
+                codes::WRONG_PAYLOAD_COUNT,
+                span,
+                format!(
+                    "expected {} for `{}.{}`, found {}",
+                    count(variant.payload.len(), "value"),
+                    _enum.name,
+                    variant.name,
+                    values.len()
+                ),
+            )
+            .with_note(variant_declaration(_enum, variant.name))];
+        }
+        let mut diagnostics = vec![];
+        for (value, (name, type_)) in values.iter().zip(&variant.payload) {
+            let declared_as = format!("`{}` of `{}.{}` is declared as `{}`", name, _enum.name, variant.name, type_.to_plain_string());
+            if let Some(diagnostic) = literal_mismatch(type_, value) {
+                diagnostics.push(diagnostic.with_note(declared_as));
+                continue;
+            }
+            if let Some(found) = self.type_of_place(value, scope).filter(|found| !fits(type_, found)) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        codes::MISMATCHED_TYPES,
+                        value.1,
+                        format!("mismatched types: expected `{}`, found `{}`", type_.to_plain_string(), found.to_plain_string()),
+                    )
+                    .with_note(declared_as),
+                );
+            }
+        }
+        diagnostics
+    }
+
+    /// the arm has to name a variant of the enum being matched, the types of what it binds come back (None when the checker doesnt know them)
+    fn check_pattern(&self, pattern: &VariantPattern, matched_enum: Option<&Enum>) -> Result<Vec<Option<Type_<'src>>>, Diagnostic> {
+        let Some(_enum) = self.enum_named(&pattern.enum_name) else {
+            let diagnostic = Diagnostic::error(
+                codes::UNKNOWN_TYPE,
+                pattern.span,
+                format!("cannot find enum `{}`", pattern.enum_name),
+            );
+            return Err(match closest_match(&pattern.enum_name, self.enums.iter().map(|_enum| _enum.name.as_str())) {
+                Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+                None => diagnostic,
+            });
+        };
+        if let Some(matched_enum) = matched_enum.filter(|matched_enum| matched_enum.name != _enum.name) {
+            return Err(Diagnostic::error(
+                codes::MISMATCHED_TYPES,
+                pattern.span,
+                format!("mismatched types: expected a variant of `{}`, found one of `{}`", matched_enum.name, _enum.name),
+            ));
+        }
+        let Some(variant) = _enum.variant(pattern.variant) else {
+            return Err(unknown_variant_error(_enum, &pattern.variant, pattern.span));
+        };
+        //no parentheses means the payload isnt needed
+        if !pattern.bindings.is_empty() && pattern.bindings.len() != variant.payload.len() {
+            return Err(Diagnostic::error(
+                codes::WRONG_PAYLOAD_COUNT,
+                pattern.span,
+                format!(
+                    "expected {} for `{}.{}`, found {}",
+                    count(variant.payload.len(), "binding"),
+                    _enum.name,
+                    variant.name,
+                    pattern.bindings.len()
+                ),
+            )
+            .with_note(variant_declaration(_enum, variant.name)));
+        }
+        Ok(variant.payload.iter().map(|(_, type_)| Some(type_.clone())).collect())
+    }
+
+    fn class_named(&self, name: &str) -> Option<&Class<'src>> {
+        self.classes.iter().find(|_class| _class.name == name)
+    }
+
+    /// the member has to be a field or method of the class of the object, members of objects the checker doesnt know the class of arent checked
+    fn check_member(&self, access: &MemberAccess, scope: &Scope<'src>) -> Option<Diagnostic> {
+        let class = self.class_named(self.type_of_place(&access.object, scope)?.name)?;
+        let is_field = class.fields.iter().any(|field| field.name == access.name);
+        let is_method = class.methods.iter().any(|method| method.name == access.name);
+        let (kind, names): (&str, Vec<&str>) = match (&access.args, is_field, is_method) {
+            (None, true, _) | (Some(_), _, true) => return None,
+            (None, false, true) => {
+                return Some(
+                    Diagnostic::error(
+                        codes::UNKNOWN_MEMBER,
+                        access.name_span,
+                        format!("`{}` is a method of `{}`, not a field", access.name, class.name),
+                    )
+                    .with_suggestion(format!("call it: `{}()`", access.name)),
+                );
+            }
+            (Some(_), true, false) => {
+                return Some(Diagnostic::error(
+                    codes::UNKNOWN_MEMBER,
+                    access.name_span,
+                    format!("`{}` is a field of `{}`, not a method", access.name, class.name),
+                ));
+            }
+            (None, false, false) => ("field", class.fields.iter().map(|field| field.name.as_str()).collect()),
+            (Some(_), false, false) => ("method", class.methods.iter().map(|method| method.name.as_str()).collect()),
+        };
+        let diagnostic = Diagnostic::error(
+            codes::UNKNOWN_MEMBER,
+            access.name_span,
+            format!("no {} `{}` on `{}`", kind, access.name, class.name),
+        );
+        Some(match closest_match(&access.name, names.into_iter()) {
+            Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+            None => diagnostic,
+        })
+    }
+}
+
+/// every construction gets all the fields of its class in the order the class declares them,
+/// the ones that were left out get the default value of the field so the backends can pass them all to the constructor
+fn fill_in_omitted_fields<'src>(construction: &mut Construction<'src>, class_fields: &[(Symbol<'src>, Vec<(Symbol<'src>, Expression<'src>)>)]) {
+    let Some((_, fields)) = class_fields.iter().find(|(name, _)| *name == construction.class_name) else {
+        return;
+    };
+    let mut given = std::mem::take(&mut construction.fields);
+    for (name, default_value) in fields {
+        match given.iter().position(|(given_name, _, _)| given_name == name) {
+            Some(index) => construction.fields.push(given.remove(index)),
+            //a missing required field was already reported
+            None if default_value.0 == ExpressionPiece::Placeholder(false) => {}
+            None => construction.fields.push((*name, default_value.1, default_value.clone())),
+        }
+    }
+}
+
+/// a field or method a method used without a prefix, as an access on the object the method was called on
+fn member_of_this<'src>(name: Symbol<'src>, span: Span, args: Option<Vec<Expression<'src>>>) -> ExpressionPiece<'src> {
+    let this = Expression(ExpressionPiece::Variable(sym::THIS), Span::new(span.start, span.start));
+    ExpressionPiece::MemberAccess(MemberAccess {
+        object: Box::new(this),
+        name,
+        name_span: Span::new(span.start, span.start + name.len()),
+        args,
+    })
+}
+
+/// `Shape.Circle(1.5)` as a VariantValue when Shape is an enum that has the variant, the values are taken out of the access
+fn variant_value<'src>(access: &mut MemberAccess<'src>, enums: &[Enum<'src>]) -> Option<VariantValue<'src>> {
+    let ExpressionPiece::Variable(enum_name) = access.object.0 else {
+        return None;
+    };
+    let variant = enums.iter().find(|_enum| _enum.name == enum_name)?.variant(access.name)?;
+    let values = access.args.take().unwrap_or_default();
+    Some(VariantValue {
+        enum_name,
+        variant: access.name,
+        payload: variant.payload.iter().map(|(name, _)| *name).zip(values).collect(),
+    })
+}
+
+/// tells every arm in the body which names of the payload its bindings stand for, nested matches are in the same body so they get done too
+fn name_bound_payloads<'src>(body: &mut [ValidInCodeBlock<'src>], enums: &[Enum<'src>]) {
+    for statement in body {
+        let ValidInCodeBlock::MatchArmStartMarker(Some(pattern), _) = statement else {
+            continue;
+        };
+        let _enum = enums.iter().find(|_enum| _enum.name == pattern.enum_name);
+        if let Some(variant) = _enum.and_then(|_enum| _enum.variant(pattern.variant)) {
+            pattern.payload_names = variant.payload.iter().map(|(name, _)| *name).collect();
+        }
+    }
+}
+
+fn unknown_variant_error(_enum: &Enum, name: &str, span: Span) -> Diagnostic {
+    let diagnostic = Diagnostic::error(codes::UNKNOWN_MEMBER, span, format!("no variant `{}` on `{}`", name, _enum.name));
+    match closest_match(name, _enum.variants.iter().map(|variant| variant.name.as_str())) {
+        Some(suggestion) => diagnostic.with_suggestion(format!("did you mean `{}`", suggestion)),
+        None => diagnostic,
+    }
+}
+
+/// how the variant is written in the enum, like `Shape.Circle(float radius)`
+fn variant_declaration(_enum: &Enum, variant: Symbol) -> String {
+    let payload = _enum.variant(variant).map_or(vec![], |variant| {
+        variant.payload.iter().map(|(name, type_)| format!("{} {}", type_.to_plain_string(), name)).collect()
+    });
+    format!("`{}.{}` is declared as `{}({})`", _enum.name, variant, variant, payload.join(", "))
+}
+
+fn count(n: usize, what: &str) -> String {
+    format!("{} {}{}", n, what, if n == 1 { "" } else { "s" })
+}
+
+/// an arm after the _ arm or after another arm for the same variant never runs, the backends leave it out
+fn unreachable_arm_warning(open_match: &OpenMatch, pattern: Option<&VariantPattern>, arm_span: Span) -> Option<Diagnostic> {
+    let note = if open_match.has_wildcard {
+        "the `_` arm above it already matches everything".to_string()
+    } else {
+        let pattern = pattern.filter(|pattern| open_match.covered.contains(&pattern.variant))?;
+        format!("`{}.{}` is already matched by an arm above it", pattern.enum_name, pattern.variant)
+    };
+    Some(Diagnostic::warning(codes::UNREACHABLE_ARM, arm_span, "unreachable match arm").with_note(note))
+}
+
+/// every variant of the enum needs an arm unless theres a _ arm, matches of something the checker doesnt know the type of arent checked
+fn check_exhaustive(open_match: OpenMatch) -> Option<Diagnostic> {
+    let matched_enum = open_match.matched_enum.filter(|_| !open_match.has_wildcard)?;
+    let missing: Vec<String> = matched_enum
+        .variants
+        .iter()
+        .filter(|variant| !open_match.covered.contains(&variant.name))
+        .map(|variant| format!("`{}.{}`", matched_enum.name, variant.name))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    Some(
+        Diagnostic::error(
+            codes::NON_EXHAUSTIVE_MATCH,
+            open_match.subject_span,
+            format!("non-exhaustive match: {} not covered", missing.join(", ")),
+        )
+        .with_suggestion("add an arm for each of them, or a `_ => { }` arm for everything else"),
+    )
+}
+
+/// the types of the loop variables of a for, worked out from the sub_types of what it goes over.
+/// None where the checker doesnt know the type of the iterable
+fn loop_variable_types<'src>(head: &ForHead<'src>, iterable_type: Option<Type_<'src>>) -> Result<Vec<Option<Type_<'src>>>, Diagnostic> {
+    match &head.iterable.0 {
+        ExpressionPiece::FunctionCall(range) if range.name == ".." => {
+            for end in &range.params {
+                if let Some(found) = literal_type(end).filter(|found| *found != "int") {
+                    return Err(Diagnostic::error(
+                        codes::MISMATCHED_TYPES,
+                        end.1,
+                        format!("mismatched types: a range goes between two ints, found `{}`", found),
+                    ));
+                }
+            }
+            return check_loop_variable_count(head, "a range", 1).map(|_| vec![Some(simple_type("int"))]);
+        }
+        _ => {}
+    }
+    let Some(iterable_type) = iterable_type else {
+        return Ok(vec![None; head.names.len()]);
+    };
+    match iterable_type.name {
+        "array" => check_loop_variable_count(head, "an array", 1).map(|_| vec![Some(iterable_type.sub_types[0].clone())]),
+        "map" => check_loop_variable_count(head, "a map", 2).map(|_| iterable_type.sub_types.iter().cloned().map(Some).collect()),
+        "string" => check_loop_variable_count(head, "a string", 1).map(|_| vec![Some(simple_type("char"))]),
+        _ => Err(Diagnostic::error(
+            codes::NOT_ITERABLE,
+            head.iterable.1,
+            format!("`{}` cant be looped over", iterable_type.to_plain_string()),
+        )
+        .with_note("a for goes over an array, a map, a string or a range like `0..n`")),
+    }
+}
+
+fn check_loop_variable_count(head: &ForHead, what: &str, expected: usize) -> Result<(), Diagnostic> {
+    if head.names.len() == expected {
+        return Ok(());
+    }
+    let span = Span::new(head.names[0].1.start, head.names[head.names.len() - 1].1.end);
+    let diagnostic = Diagnostic::error(
+        codes::NOT_ITERABLE,
+        span,
+        format!("{} is looped over with {} variable{}, not {}", what, expected, if expected == 1 { "" } else { "s" }, head.names.len()),
+    );
+    Err(match expected {
+        1 => diagnostic.with_suggestion(format!("loop with `for {} in ...`", head.names[0].0)),
+        _ => diagnostic.with_suggestion(format!("loop with `for key, {} in ...`", head.names[0].0)),
+    })
+}
+
+fn simple_type(name: &str) -> Type_<'_> {
+    Type_ {
+        name,
+        ..Default::default()
+    }
+}
+
+/// the type of an expression thats just a literal, None for anything the checker cant work out yet
+fn literal_type(expression: &Expression) -> Option<&'static str> {
+    expression_type(expression, &|_| None)
+}
+
+/// the type of an expression made of literals and operators, place_type gives the types of the variables, members and elements in it.
+/// None for anything the checker cant work out yet
+fn expression_type<'a>(expression: &Expression, place_type: &dyn Fn(&Expression) -> Option<&'a str>) -> Option<&'a str> {
+    match &expression.0 {
+        ExpressionPiece::StringLiteral(_) => Some("string"),
+        ExpressionPiece::NumberLiteral(_, NumberKind::Int { .. }) => Some("int"),
+        ExpressionPiece::NumberLiteral(_, NumberKind::Float) => Some("float"),
+        ExpressionPiece::BoolLiteral(_) => Some("bool"),
+        ExpressionPiece::CharLiteral(_) => Some("char"),
+        //-1 and !true are still literals as far as the checker is concerned
+        ExpressionPiece::FunctionCall(call) if call.params.len() == 1 => match (call.name.as_str(), expression_type(&call.params[0], place_type)) {
+            ("-", Some(found @ ("int" | "float"))) => Some(found),
+            ("!", Some("bool")) => Some("bool"),
+            ("~", Some("int")) => Some("int"),
+            _ => None,
+        },
+        //a comparison is a bool whatever it compares
+        ExpressionPiece::FunctionCall(call) if COMPARISON_OPERATORS.contains(&call.name.as_str()) || LOGICAL_OPERATORS.contains(&call.name.as_str()) => {
+            Some("bool")
+        }
+        ExpressionPiece::FunctionCall(call) if call.params.len() == 2 => {
+            match (call.name.as_str(), expression_type(&call.params[0], place_type)?, expression_type(&call.params[1], place_type)?) {
+                ("+", "string", "string") => Some("string"),
+                ("+" | "-" | "*" | "/" | "%", left @ ("int" | "float"), right @ ("int" | "float")) => {
+                    Some(if left == "float" || right == "float" { "float" } else { "int" })
+                }
+                ("<<" | ">>" | "&" | "|" | "^", "int", "int") => Some("int"),
+                _ => None,
+            }
+        }
+        ExpressionPiece::FunctionCall(_) => None,
+        _ => place_type(expression),
+    }
+}
+
+fn check_literal_type(declared: &Type_, value: &Expression, name: &str) -> Option<Diagnostic> {
+    let diagnostic = literal_mismatch(declared, value)?
+        .with_note(format!("`{}` is declared as `{}`", name, declared.to_plain_string()));
+    Some(match (&value.0, declared.name, literal_type(value)) {
+        (ExpressionPiece::NullLiteral, _, _) => {
+            diagnostic.with_suggestion(format!("make it optional: `{}?`", declared.to_plain_string()))
+        }
+        (_, "int", Some("float")) => diagnostic.with_suggestion(format!("declare `{}` as a float", name)),
+        _ => diagnostic,
+    })
+}
+
+/// a value thats not a literal has to fit the declared type too, when the checker knows the type it has
+fn check_declared_type(declared: &Type_, value: &Expression, found: Option<Type_>, name: &str) -> Option<Diagnostic> {
+    let found = found?;
+    if fits(declared, &found) {
+        return None;
+    }
+    Some(
+        Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            value.1,
+            format!(
+                "mismatched types: expected `{}`, found `{}`",
+                declared.to_plain_string(),
+                found.to_plain_string()
+            ),
+        )
+        .with_note(format!("`{}` is declared as `{}`", name, declared.to_plain_string())),
+    )
+}
+
+/// the error for a literal that doesnt fit the declared type, without the notes on where the type comes from.
+/// the elements of array and map literals are checked against the sub_types of the declared type
+fn literal_mismatch(declared: &Type_, value: &Expression) -> Option<Diagnostic> {
+    match &value.0 {
+        ExpressionPiece::ArrayLiteral(elements) if declared.name == "array" => {
+            return elements.iter().find_map(|element| literal_mismatch(&declared.sub_types[0], element));
+        }
+        ExpressionPiece::MapLiteral(entries) if declared.name == "map" => {
+            return entries.iter().find_map(|(key, value)| {
+                literal_mismatch(&declared.sub_types[0], key).or_else(|| literal_mismatch(&declared.sub_types[1], value))
+            });
+        }
+        ExpressionPiece::ArrayLiteral(elements) => {
+            let diagnostic = Diagnostic::error(
+                codes::MISMATCHED_TYPES,
+                value.1,
+                format!("mismatched types: expected `{}`, found an array", declared.to_plain_string()),
+            );
+            return Some(match (declared.name, elements.is_empty()) {
+                ("map", true) => diagnostic.with_suggestion("an empty map is written `[:]`"),
+                _ => diagnostic,
+            });
+        }
+        ExpressionPiece::MapLiteral(_) => {
+            return Some(Diagnostic::error(
+                codes::MISMATCHED_TYPES,
+                value.1,
+                format!("mismatched types: expected `{}`, found a map", declared.to_plain_string()),
+            ));
+        }
+        ExpressionPiece::Lambda(function) => return signature_mismatch(declared, function, value.1),
+        _ => {}
+    }
+    //null is only allowed for types that say they can be empty
+    if value.0 == ExpressionPiece::NullLiteral {
+        if declared.is_optional || declared.is_pointer {
+            return None;
+        }
+        return Some(Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            value.1,
+            format!("`null` can only be used for optional types, and `{}` is not optional", declared.to_plain_string()),
+        ));
+    }
+    let found = literal_type(value)?;
+    if !declared.sub_types.is_empty() || !LITERAL_TYPES.contains(&declared.name) || declared.name == found {
+        return None;
+    }
+    //an int fits in a float without losing anything
+    if declared.name == "float" && found == "int" {
+        return None;
+    }
+    Some(Diagnostic::error(
+        codes::MISMATCHED_TYPES,
+        value.1,
+        format!("mismatched types: expected `{}`, found `{}`", declared.name, found),
+    ))
+}
+
+/// a function value has to take the same params as the function type it is used as and give back something that fits its return type
+fn signature_mismatch(declared: &Type_, function: &Function, span: Span) -> Option<Diagnostic> {
+    if declared.name != "function" {
+        return Some(Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            span,
+            format!("mismatched types: expected `{}`, found a function", declared.to_plain_string()),
+        ));
+    }
+    //function<(params), return>, anything else was already reported as a bad type
+    let [declared_params, declared_return] = &declared.sub_types[..] else {
+        return None;
+    };
+    let return_type = match (function.return_type.name, &function.body[..]) {
+        (INFERRED_TYPE, [ValidInCodeBlock::Return(Some(value), _)]) => match literal_type(value) {
+            Some(found) => simple_type(found),
+            None => declared_return.clone(),
+        },
+        _ => function.return_type.clone(),
+    };
+    let found = Type_ {
+        name: "function",
+        sub_types: vec![
+            Type_ {
+                name: "tuple",
+                sub_types: function.params.iter().map(|param| param.type_.clone()).collect(),
+                ..Default::default()
+            },
+            return_type,
+        ],
+        ..Default::default()
+    };
+    let params_fit = declared_params.sub_types.len() == function.params.len()
+        && declared_params.sub_types.iter().zip(&function.params).all(|(declared, param)| declared.same_type(&param.type_));
+    if params_fit && fits(declared_return, &found.sub_types[1]) {
+        return None;
+    }
+    Some(Diagnostic::error(
+        codes::MISMATCHED_TYPES,
+        span,
+        format!(
+            "mismatched types: expected `{}`, found `{}`",
+            declared.to_plain_string(),
+            found.to_plain_string()
+        ),
+    ))
+}
+
+/// && || and ! only take bools, arithmetic only takes numbers (and strings for +), bitwise operators only take ints
+/// and the two sides of a comparison have to be the same type (an int can be compared to a float).
+/// only operands the checker knows the type of get checked, operand_types has one entry for each of the params of the call
+fn check_operator(call: &FunctionCall, span: Span, operand_types: &[Option<&str>]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let operator = call.name.as_str();
+    let (allowed, works_on): (&[&str], &str) = if LOGICAL_OPERATORS.contains(&operator) {
+        (&["bool"], "bools")
+    } else if operator == "+" && call.params.len() == 2 {
+        (&["int", "float", "string"], "numbers and strings")
+    } else if ARITHMETIC_OPERATORS.contains(&operator) {
+        (&["int", "float"], "numbers")
+    } else if BITWISE_OPERATORS.contains(&operator) {
+        (&["int"], "ints")
+    } else {
+        (&[], "")
+    };
+    for (operand, found) in call.params.iter().zip(operand_types) {
+        if let Some(found) = found.filter(|found| !allowed.is_empty() && !allowed.contains(found)) {
+            diagnostics.push(
+                Diagnostic::error(
+                    codes::MISMATCHED_TYPES,
+                    operand.1,
+                    format!("mismatched types: `{}` cant be used on `{}`", operator, found),
+                )
+                .with_note(format!("`{}` only works on {}", operator, works_on)),
+            );
+        }
+    }
+    let [Some(left), Some(right)] = operand_types else {
+        return diagnostics;
+    };
+    let both_numbers = ["int", "float"].contains(left) && ["int", "float"].contains(right);
+    if COMPARISON_OPERATORS.contains(&operator) && left != right && !both_numbers {
+        diagnostics.push(Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            span,
+            format!("mismatched types: cant compare `{}` with `{}`", left, right),
+        ));
+    }
+    //each side on its own is fine, but a string cant be added to a number
+    if operator == "+" && diagnostics.is_empty() && left != right && !both_numbers {
+        diagnostics.push(Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            span,
+            format!("mismatched types: cant add `{}` and `{}`", left, right),
+        ));
+    }
+    diagnostics
+}
+
+/// the condition of an if, else if or while has to be a bool
+fn check_condition(condition: &Expression, found: Option<&str>) -> Option<Diagnostic> {
+    let found = found.filter(|found| *found != "bool")?;
+    Some(
+        Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            condition.1,
+            format!("mismatched types: expected `bool`, found `{}`", found),
+        )
+        .with_note("conditions have to be a bool"),
+    )
+}
+
+/// a function with a return type cant get to its closing } without giving back a value
+fn check_every_path_returns(function: &Function) -> Option<Diagnostic> {
+    let declared = &function.return_type;
+    if declared.name == "void" || declared.name == INFERRED_TYPE || always_returns(&function.body, 0..function.body.len()) {
+        return None;
+    }
+    let closing_brace = Span::new(function.span.end.saturating_sub(1), function.span.end);
+    Some(
+        Diagnostic::error(
+            codes::MISSING_RETURN,
+            closing_brace,
+            format!("`{}` can reach its end without returning a value", function.name),
+        )
+        .with_note(format!("`{}` returns `{}` so every path through it has to end in a return", function.name, declared.to_plain_string())),
+    )
+}
+
+/// a function that returns void cant give back a value and every other function has to give back one of its return type.
+/// the value is checked when its a literal or the checker knows the type of the variables and operators in it, thats found
+fn check_return(function: &Function, value: Option<&Expression>, span: Span, found: Option<Type_>) -> Option<Diagnostic> {
+    let declared = &function.return_type;
+    //a => function gives back whatever its expression does
+    if declared.name == INFERRED_TYPE {
+        return None;
+    }
+    let returns = format!("`{}` returns `{}`", function.name, declared.to_plain_string());
+    let Some(value) = value else {
+        if declared.name == "void" {
+            return None;
+        }
+        return Some(
+            Diagnostic::error(codes::MISMATCHED_TYPES, span, format!("expected a value of type `{}` to be returned", declared.to_plain_string()))
+                .with_note(returns),
+        );
+    };
+    if declared.name == "void" {
+        return Some(
+            Diagnostic::error(codes::MISMATCHED_TYPES, value.1, format!("`{}` doesnt return a value", function.name))
+                .with_suggestion(format!("remove the value or give `{}` a return type after its params", function.name)),
+        );
+    }
+    if let Some(diagnostic) = literal_mismatch(declared, value) {
+        return Some(diagnostic.with_note(returns));
+    }
+    let found = found?;
+    if fits(declared, &found) {
+        return None;
+    }
+    Some(
+        Diagnostic::error(
+            codes::MISMATCHED_TYPES,
+            value.1,
+            format!(
+                "mismatched types: expected `{}`, found `{}`",
+                declared.to_plain_string(),
+                found.to_plain_string()
+            ),
+        )
+        .with_note(returns),
+    )
+}
+
+/// whether a value of type found can be used where declared is expected
+fn fits(declared: &Type_, found: &Type_) -> bool {
+    if declared.same_type(found) {
+        return true;
+    }
+    //an int fits in a float and anything fits in the optional version of its type
+    let widened = Type_ {
+        name: if declared.name == "float" && found.name == "int" { "float" } else { found.name },
+        is_optional: found.is_optional || declared.is_optional,
+        ..found.clone()
+    };
+    declared.same_type(&widened)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::{codes, Severity};
+    use crate::file::File;
+    use crate::project_basic_utils::tokenizer::Tokenizer;
+
+    /// the codes of everything the checker reports for the code, with whether they are errors
+    fn check(code: &str) -> Vec<(Severity, &'static str)> {
+        let mut file = File::new(Tokenizer::new(file!(), line!() as usize, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        file.tokenizer.diagnostics.diagnostics.iter().map(|diagnostic| (diagnostic.severity, diagnostic.code)).collect()
+    }
+
+    #[test]
+    fn test_unknown_names_suggest_the_closest_one() {
+        let code = "class Person {
+                int age
+            }
+
+            function greet(int times) int {
+                return times
+            }
+
+            function main() void {
+                let Persn somebody = Person{ age: 1 }
+                let int count = 2
+                let int total = cont + gret(count)
+            }
+            ";
+        let mut file = File::new(Tokenizer::new(file!(), line!() as usize, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        let suggestions: Vec<(&str, Option<&str>)> = file
+            .tokenizer
+            .diagnostics
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            suggestions,
+            vec![
+                (codes::UNKNOWN_TYPE, Some("there is no type Persn, did you mean Person")),
+                (codes::UNKNOWN_NAME, Some("did you mean `count`")),
+                (codes::UNKNOWN_FUNCTION, Some("did you mean `greet`")),
+            ]
+        );
+        assert!(file.tokenizer.render_diagnostics().contains("= help: there is no type Persn, did you mean Person"));
+    }
+
+    #[test]
+    fn test_unreachable_match_arms_are_warnings() {
+        let diagnostics = check(
+            "enum Shape { Circle(float radius), Empty }
+
+            function main() void {
+                match Shape.Empty {
+                    Shape.Circle(r) => { }
+                    Shape.Circle => { }
+                    _ => { }
+                    Shape.Empty => { }
+                }
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Warning, codes::UNREACHABLE_ARM); 2]);
+    }
+
+    #[test]
+    fn test_matches_have_to_cover_every_variant() {
+        let code = "enum Shape { Circle(float radius), Square(float side), Empty }
+
+            function area(Shape shape) float {
+                match shape {
+                    Shape.Circle(r) => { return r * r * 3.14 }
+                    Shape.Empty => { return 0.0 }
+                }
+                return 0.0
+            }
+            ";
+        let mut file = File::new(Tokenizer::new(file!(), line!() as usize, code));
+        file.generate_syntax_tree_from_source_code();
+        file.type_check();
+        let messages: Vec<(&str, &str)> = file
+            .tokenizer
+            .diagnostics
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(messages, vec![(codes::NON_EXHAUSTIVE_MATCH, "non-exhaustive match: `Shape.Square` not covered")]);
+
+        let diagnostics = check(
+            "enum Shape { Circle(float radius), Square(float side), Empty }
+
+            function area(Shape shape) float {
+                match shape {
+                    Shape.Circle(r) => { return r * r * 3.14 }
+                    Shape.Square(s) => { return s * s }
+                    Shape.Empty => { return 0.0 }
+                }
+                return 0.0
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn test_type_names_are_unique() {
+        let diagnostics = check(
+            "class Shape {
+                int id
+            }
+            enum Color { Red }
+            enum Shape { Circle }
+            class Color { }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::DUPLICATE_DEFINITION); 2]);
+    }
+
+    #[test]
+    fn test_every_path_has_to_return() {
+        let returning = check(
+            "enum Shape { Circle(float radius), Empty }
+
+            function sign(int n) int {
+                if n < 0 {
+                    return -1
+                } else if n == 0 {
+                    return 0
+                } else {
+                    return 1
+                }
+            }
+            function area(Shape s) float {
+                match s {
+                    Shape.Circle(r) => { return r * r }
+                    _ => { return 0.0 }
+                }
+            }
+            function nothing() void { }
+            ",
+        );
+        assert_eq!(returning, vec![]);
+        let falling_off = check(
+            "function positive(int n) int {
+                if n > 0 {
+                    return n
+                }
+            }
+            function countdown(int n) int {
+                while n > 0 {
+                    return n
+                }
+            }
+            function main() void {
+                let function<(int), int> twice = function(int a) int { }
+            }
+            ",
+        );
+        assert_eq!(falling_off, vec![(Severity::Error, codes::MISSING_RETURN); 3]);
+    }
+
+    #[test]
+    fn test_break_and_continue_need_a_loop() {
+        let fine = check(
+            "function main(int n) void {
+                while n > 0 {
+                    if n == 3 {
+                        break
+                    }
+                    continue
+                }
+                for i in 0..n {
+                    continue
+                }
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "function main(int n) void {
+                break
+                if n > 0 {
+                    continue
+                }
+                for i in 0..n { }
+                break
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::OUTSIDE_OF_LOOP); 3]);
+    }
+
+    #[test]
+    fn test_operands_get_their_type_from_the_scope() {
+        let fine = check(
+            "class Box { int size }
+
+            function main(bool bb, string s, Box b) void {
+                let bool small = b.size * 2 >= 1.5 && !bb
+                let int shifted = b.size << 2 | 1
+                let string shout = s + \"!\"
+                let float half = b.size / 2
+                if s == \"a\" || b.size != 0 { }
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "class Box { int size }
+
+            function main(bool bb, string s, Box b) void {
+                let int y = bb + 1
+                let bool z = s < 3
+                let bool both = bb && b.size
+                let string joined = s + b.size
+                let int length = s
+                if b.size { }
+            }
+            function twice(int a) string {
+                return a * 2
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::MISMATCHED_TYPES); 7]);
+    }
+
+    #[test]
+    fn test_ranges_only_go_in_a_for() {
+        let diagnostics = check(
+            "let int everything = 0..10
+
+            function main(int n) void {
+                let int r = 0..5
+                for i in 0..n + 1 {
+                    let int square = i * i
+                }
+                take(1..n)
+            }
+            function take(int a) void { }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::RANGE_OUTSIDE_FOR); 3]);
+    }
+
+    #[test]
+    fn test_members_have_to_be_on_the_class() {
+        let fine = check(
+            "class Car {
+                int speed = 1
+                function honk() int {
+                    return speed
+                }
+            }
+
+            function main(Car car) void {
+                let int fast = car.speed + car.honk()
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "class Car {
+                int speed = 1
+                function honk() int {
+                    return speed
+                }
+            }
+
+            function main(Car car) void {
+                let int a = car.sped
+                let int b = car.honks()
+                let int c = car.honk
+                let int d = car.speed()
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::UNKNOWN_MEMBER); 4]);
+    }
+
+    #[test]
+    fn test_only_collections_are_indexable() {
+        let fine = check(
+            "function main(array<int> numbers, map<string, int> ages, string name) void {
+                let int first = numbers[0]
+                let int age = ages[\"bob\"]
+                let char letter = name[1]
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "class Box { int size }
+
+            function main(int n, Box b, bool flag, map<string, int> ages) void {
+                let int a = n[0]
+                let int c = b[1]
+                let int d = flag[2]
+                let int e = ages[3]
+            }
+            ",
+        );
+        assert_eq!(
+            diagnostics,
+            vec![
+                (Severity::Error, codes::NOT_INDEXABLE),
+                (Severity::Error, codes::NOT_INDEXABLE),
+                (Severity::Error, codes::NOT_INDEXABLE),
+                (Severity::Error, codes::MISMATCHED_TYPES),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constructions_give_every_field_once() {
+        let fine = check(
+            "class Person {
+                int id
+                string name = \"nobody\"
+            }
+
+            function main() void {
+                let Person named = Person{ id: 1, name: \"al\" }
+                let Person unnamed = Person{ id: 2 }
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "class Person {
+                int id
+                string name = \"nobody\"
+            }
+
+            function main() void {
+                let Person nameless = Person{ name: \"al\" }
+                let Person twice = Person{ id: 1, id: 2 }
+                let Person aged = Person{ id: 3, age: 4 }
+            }
+            ",
+        );
+        assert_eq!(
+            diagnostics,
+            vec![
+                (Severity::Error, codes::MISSING_FIELD),
+                (Severity::Error, codes::DUPLICATE_FIELD),
+                (Severity::Error, codes::UNKNOWN_MEMBER),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lambdas_have_to_fit_the_function_type() {
+        let fine = check(
+            "function main() void {
+                let function<(int, int), int> add = function(int a, int b) int {
+                    return a + b
+                }
+                let function<(int), int> double = function(int a) => a * 2
+                let function<(string), bool> empty = function(string s) => s == \"\"
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "function main() void {
+                let function<(int, int), int> too_few = function(int a) int {
+                    return a
+                }
+                let function<(int), int> wrong_param = function(string s) int {
+                    return 1
+                }
+                let function<(int), int> wrong_return = function(int a) string {
+                    return \"a\"
+                }
+                let function<(int), int> short_wrong_return = function(int a) => \"a\"
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::MISMATCHED_TYPES); 4]);
+    }
+
+    #[test]
+    fn test_loop_variables_get_the_element_type() {
+        let fine = check(
+            "function main(array<string> names, map<string, int> ages, int n) void {
+                for name in names {
+                    let string copy = name
+                }
+                for name, age in ages {
+                    let string key = name
+                    let int value = age
+                }
+                for i in 0..n {
+                    let int index = i
+                }
+            }
+            ",
+        );
+        assert_eq!(fine, vec![]);
+        let diagnostics = check(
+            "function main(array<string> names, map<string, int> ages, int n) void {
+                for name in names {
+                    let int wrong = name
+                }
+                for name, age in ages {
+                    let int key = name
+                    let string value = age
+                }
+                for i in 0..n {
+                    let string index = i
+                }
+            }
+            ",
+        );
+        assert_eq!(diagnostics, vec![(Severity::Error, codes::MISMATCHED_TYPES); 4]);
+    }
+
+    #[test]
+    fn test_only_collections_are_iterable() {
+        let diagnostics = check(
+            "class Box { int size }
+
+            function main(int n, Box b, array<int> numbers, map<int, int> squares) void {
+                for i in n { }
+                for part in b { }
+                for index, number in numbers { }
+                for square in squares { }
+                for i in 0..2.5 { }
+            }
+            ",
+        );
+        assert_eq!(
+            diagnostics,
+            vec![
+                (Severity::Error, codes::NOT_ITERABLE),
+                (Severity::Error, codes::NOT_ITERABLE),
+                (Severity::Error, codes::NOT_ITERABLE),
+                (Severity::Error, codes::NOT_ITERABLE),
+                (Severity::Error, codes::MISMATCHED_TYPES),
+            ]
+        );
+    }
+}